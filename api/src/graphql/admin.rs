@@ -1,6 +1,9 @@
+mod mutation;
 mod query;
+mod types;
 
 use crate::graphql::GraphResult;
+use crate::graphql::admin::mutation::MutationRoot;
 use crate::graphql::admin::query::QueryRoot;
 use crate::graphql::shared::schema::new_schema_builder;
 use actix_web::HttpRequest;
@@ -10,7 +13,7 @@ use app::errors::AppError;
 use app::errors::Kind::BadRequest;
 use app::errors::Kind::Unauthorized;
 use app::{AppResult, domain};
-use async_graphql::{Context, EmptyMutation, EmptySubscription};
+use async_graphql::{Context, EmptySubscription};
 use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
 use async_trait::async_trait;
 use std::sync::Arc;
@@ -35,7 +38,7 @@ impl<'a> AppContext for Context<'_> {
     }
 }
 
-pub type Schema = async_graphql::Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+pub type Schema = async_graphql::Schema<QueryRoot, MutationRoot, EmptySubscription>;
 
 #[derive(Clone)]
 pub struct HttpHandler {
@@ -45,7 +48,8 @@ pub struct HttpHandler {
 
 impl HttpHandler {
     pub async fn new(app: app::App) -> Self {
-        let schema = new_schema_builder(app.clone(), QueryRoot::default(), EmptyMutation).finish();
+        let schema =
+            new_schema_builder(app.clone(), QueryRoot::default(), MutationRoot::default()).finish();
 
         HttpHandler {
             schema,
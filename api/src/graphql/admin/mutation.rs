@@ -0,0 +1,150 @@
+use crate::graphql::GraphResult;
+use crate::graphql::admin::AppContext;
+use crate::graphql::admin::types::api_key::{ApiKey, ApiKeyPayload, ApiKeySecretPayload};
+use crate::graphql::admin::types::dead_letter::DeadLetterActionPayload;
+use crate::graphql::admin::types::email_suppression::{EmailSuppression, EmailSuppressionPayload};
+use crate::graphql::shared::types::{BoolPayload, DateTime};
+use app::domain;
+use app::domain::api_key::Scope;
+use app::errors::Kind::BadRequest;
+use async_graphql::{Context, ID, InputObject, MergedObject, Object};
+
+#[derive(MergedObject, Default)]
+pub struct MutationRoot(DefaultMutation);
+
+#[derive(Default)]
+pub struct DefaultMutation;
+#[Object]
+impl DefaultMutation {
+    async fn api_key_issue(
+        &self,
+        ctx: &Context<'_>,
+        input: ApiKeyIssueInput,
+    ) -> GraphResult<ApiKeySecretPayload> {
+        let _uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        if input.scopes.is_empty() {
+            return Err(BadRequest.with("スコープを1つ以上指定してください").into());
+        }
+        let (api_key, secret) = domain::api_key::ApiKey::issue(
+            input.name.try_into().map_err(BadRequest.withf())?,
+            input.scopes,
+            input.expires_at.map(|v| v.0),
+        );
+
+        let tx = app.db_session.begin_tx().await?;
+        app.api_key_repository
+            .insert(tx.conn(), api_key.clone())
+            .await?;
+        tx.commit().await?;
+
+        Ok(ApiKeySecretPayload {
+            item: ApiKey::from(api_key),
+            secret: secret.as_str().to_string(),
+        })
+    }
+
+    async fn api_key_rotate(&self, ctx: &Context<'_>, id: ID) -> GraphResult<ApiKeySecretPayload> {
+        let _uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        let tx = app.db_session.begin_tx().await?;
+        let api_key = app.api_key_repository.get(tx.conn(), &id.0.into()).await?;
+        if !api_key.is_active() {
+            return Err(BadRequest
+                .with("無効なAPIキーはローテーションできません")
+                .into());
+        }
+        let (api_key, secret) = api_key.rotate();
+        app.api_key_repository
+            .update(tx.conn(), api_key.clone())
+            .await?;
+        tx.commit().await?;
+
+        Ok(ApiKeySecretPayload {
+            item: ApiKey::from(api_key),
+            secret: secret.as_str().to_string(),
+        })
+    }
+
+    async fn api_key_revoke(&self, ctx: &Context<'_>, id: ID) -> GraphResult<ApiKeyPayload> {
+        let _uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        let tx = app.db_session.begin_tx().await?;
+        let api_key = app.api_key_repository.get(tx.conn(), &id.0.into()).await?;
+        let api_key = api_key.revoke();
+        app.api_key_repository
+            .update(tx.conn(), api_key.clone())
+            .await?;
+        tx.commit().await?;
+
+        Ok(ApiKey::from(api_key).into())
+    }
+
+    // 送信停止を解除する。再度バウンスした場合は改めて登録される
+    async fn email_suppression_delete(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+    ) -> GraphResult<EmailSuppressionPayload> {
+        let _uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        let tx = app.db_session.begin_tx().await?;
+        let id = id.0.into();
+        let suppression = app.email_suppression_repository.get(tx.conn(), &id).await?;
+        app.email_suppression_repository
+            .delete(tx.conn(), &id)
+            .await?;
+        tx.commit().await?;
+
+        Ok(EmailSuppression::from(suppression).into())
+    }
+
+    // 投入済みでも実行時に状態を確認するため、実行前であれば取り消せる
+    async fn scheduled_task_cancel(&self, ctx: &Context<'_>, id: ID) -> GraphResult<BoolPayload> {
+        let _uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        app::worker::cancel_scheduled(app, &id.0.into()).await?;
+
+        Ok(true.into())
+    }
+
+    async fn dead_letter_redrive(
+        &self,
+        ctx: &Context<'_>,
+        ids: Vec<ID>,
+    ) -> GraphResult<DeadLetterActionPayload> {
+        let _uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        let ids = ids.into_iter().map(|v| v.0).collect::<Vec<_>>();
+        let redriven = app::dlq::redrive(app, &ids).await?;
+
+        Ok(redriven.into())
+    }
+
+    async fn dead_letter_delete(
+        &self,
+        ctx: &Context<'_>,
+        ids: Vec<ID>,
+    ) -> GraphResult<DeadLetterActionPayload> {
+        let _uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        let ids = ids.into_iter().map(|v| v.0).collect::<Vec<_>>();
+        let deleted = app::dlq::delete(app, &ids).await?;
+
+        Ok(deleted.into())
+    }
+}
+
+#[derive(InputObject)]
+struct ApiKeyIssueInput {
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    pub expires_at: Option<DateTime>,
+}
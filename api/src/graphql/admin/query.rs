@@ -1,5 +1,11 @@
 use crate::graphql::GraphResult;
 use crate::graphql::admin::AppContext;
+use crate::graphql::admin::types::api_key::{ApiKey, ApiKeyListPayload};
+use crate::graphql::admin::types::dead_letter::{DeadLetter, DeadLetterListPayload};
+use crate::graphql::admin::types::deletion_request::{DeletionRequest, DeletionRequestListPayload};
+use crate::graphql::admin::types::email_suppression::{
+    EmailSuppression, EmailSuppressionListPayload,
+};
 use async_graphql::{Context, MergedObject, Object};
 
 #[derive(MergedObject, Default)]
@@ -13,4 +19,66 @@ impl DefaultQuery {
         let uid = ctx.verified_user_id()?;
         Ok(uid.to_string())
     }
+
+    async fn api_keys(&self, ctx: &Context<'_>) -> GraphResult<ApiKeyListPayload> {
+        let _uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+        let api_keys = app.api_key_repository.find(app.db_session.conn()).await?;
+        Ok(api_keys
+            .into_iter()
+            .map(ApiKey::from)
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    async fn deletion_requests(
+        &self,
+        ctx: &Context<'_>,
+    ) -> GraphResult<DeletionRequestListPayload> {
+        let _uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+        let requests = app
+            .user_deletion_request_repository
+            .find(app.db_session.conn())
+            .await?;
+        Ok(requests
+            .into_iter()
+            .map(DeletionRequest::from)
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    async fn email_suppressions(
+        &self,
+        ctx: &Context<'_>,
+    ) -> GraphResult<EmailSuppressionListPayload> {
+        let _uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+        let suppressions = app
+            .email_suppression_repository
+            .find(app.db_session.conn())
+            .await?;
+        Ok(suppressions
+            .into_iter()
+            .map(EmailSuppression::from)
+            .collect::<Vec<_>>()
+            .into())
+    }
+
+    // 受信した時点でDLQにあるメッセージの一部を返す
+    async fn dead_letters(
+        &self,
+        ctx: &Context<'_>,
+        limit: Option<u32>,
+    ) -> GraphResult<DeadLetterListPayload> {
+        let _uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+        let limit = limit.map(|v| v as usize).unwrap_or(app::dlq::MAX_MESSAGES);
+        let dead_letters = app::dlq::list(app, limit).await?;
+        Ok(dead_letters
+            .into_iter()
+            .map(DeadLetter::from)
+            .collect::<Vec<_>>()
+            .into())
+    }
 }
@@ -0,0 +1,4 @@
+pub mod api_key;
+pub mod dead_letter;
+pub mod deletion_request;
+pub mod email_suppression;
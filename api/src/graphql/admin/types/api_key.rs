@@ -0,0 +1,60 @@
+use crate::graphql::shared::types::DateTime;
+use app::domain;
+use app::domain::api_key::Scope;
+use async_graphql::{ID, Object, SimpleObject};
+use derive_more::From;
+
+#[derive(Debug, Clone, From)]
+pub struct ApiKey(domain::api_key::ApiKey);
+#[Object]
+impl ApiKey {
+    async fn id(&self) -> ID {
+        ID::from(self.0.id.as_str())
+    }
+
+    async fn name(&self) -> String {
+        self.0.name.to_string()
+    }
+
+    async fn key_prefix(&self) -> String {
+        self.0.key_prefix.clone()
+    }
+
+    async fn scopes(&self) -> Vec<Scope> {
+        self.0.scopes.clone()
+    }
+
+    async fn is_active(&self) -> bool {
+        self.0.is_active()
+    }
+
+    async fn expires_at(&self) -> Option<DateTime> {
+        self.0.expires_at.map(|v| v.into())
+    }
+
+    async fn revoked_at(&self) -> Option<DateTime> {
+        self.0.revoked_at.map(|v| v.into())
+    }
+
+    async fn last_used_at(&self) -> Option<DateTime> {
+        self.0.last_used_at.map(|v| v.into())
+    }
+
+    async fn created_at(&self) -> DateTime {
+        self.0.created_at.into()
+    }
+
+    async fn updated_at(&self) -> DateTime {
+        self.0.updated_at.into()
+    }
+}
+
+// secretは発行・ローテーション時のレスポンスでのみ返す
+#[derive(SimpleObject)]
+pub struct ApiKeySecretPayload {
+    pub item: ApiKey,
+    pub secret: String,
+}
+
+crate::define_item_payload!(ApiKeyPayload, ApiKey);
+crate::define_list_payload!(ApiKeyListPayload, ApiKey);
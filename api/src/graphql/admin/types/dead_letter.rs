@@ -0,0 +1,60 @@
+use crate::graphql::shared::types::DateTime;
+use app::dlq;
+use async_graphql::{ID, Json, Object, SimpleObject};
+use derive_more::From;
+use serde_json::Value;
+
+#[derive(Debug, Clone, From)]
+pub struct DeadLetter(dlq::DeadLetter);
+#[Object]
+impl DeadLetter {
+    async fn id(&self) -> ID {
+        ID::from(self.0.message.message_id.as_str())
+    }
+
+    async fn body(&self) -> String {
+        self.0.message.body.clone()
+    }
+
+    async fn task_type(&self) -> Option<String> {
+        self.0
+            .task
+            .as_ref()
+            .map(|v| v.payload.task_type().to_string())
+    }
+
+    async fn idempotency_key(&self) -> Option<String> {
+        self.0.task.as_ref().and_then(|v| v.idempotency_key.clone())
+    }
+
+    async fn payload(&self) -> Option<Json<Value>> {
+        self.0
+            .task
+            .as_ref()
+            .and_then(|v| serde_json::to_value(&v.payload).ok())
+            .map(Json)
+    }
+
+    async fn receive_count(&self) -> u32 {
+        self.0.message.receive_count
+    }
+
+    async fn sent_at(&self) -> Option<DateTime> {
+        self.0.message.sent_at.map(DateTime::from)
+    }
+}
+
+crate::define_list_payload!(DeadLetterListPayload, DeadLetter);
+
+#[derive(Debug, Clone, SimpleObject)]
+pub struct DeadLetterActionPayload {
+    // 対象が見つからなかった・失敗したIDは含まれない
+    pub ids: Vec<ID>,
+}
+impl From<Vec<String>> for DeadLetterActionPayload {
+    fn from(ids: Vec<String>) -> Self {
+        Self {
+            ids: ids.into_iter().map(ID::from).collect(),
+        }
+    }
+}
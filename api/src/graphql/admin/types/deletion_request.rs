@@ -0,0 +1,48 @@
+use crate::graphql::shared::types::DateTime;
+use app::domain;
+use app::domain::user::deletion_request::{Status, Step};
+use async_graphql::{ID, Object};
+use derive_more::From;
+
+#[derive(Debug, Clone, From)]
+pub struct DeletionRequest(domain::user::deletion_request::DeletionRequest);
+#[Object]
+impl DeletionRequest {
+    async fn id(&self) -> ID {
+        ID::from(self.0.id.as_str())
+    }
+
+    async fn user_id(&self) -> ID {
+        ID::from(self.0.user_id.as_str())
+    }
+
+    async fn status(&self) -> Status {
+        self.0.status
+    }
+
+    async fn step(&self) -> Step {
+        self.0.step
+    }
+
+    async fn attempts(&self) -> u32 {
+        self.0.attempts
+    }
+
+    async fn last_error(&self) -> Option<String> {
+        self.0.last_error.clone()
+    }
+
+    async fn completed_at(&self) -> Option<DateTime> {
+        self.0.completed_at.map(|v| v.into())
+    }
+
+    async fn created_at(&self) -> DateTime {
+        self.0.created_at.into()
+    }
+
+    async fn updated_at(&self) -> DateTime {
+        self.0.updated_at.into()
+    }
+}
+
+crate::define_list_payload!(DeletionRequestListPayload, DeletionRequest);
@@ -0,0 +1,37 @@
+use crate::graphql::shared::types::DateTime;
+use app::domain;
+use app::domain::email_suppression::Reason;
+use async_graphql::{ID, Object};
+use derive_more::From;
+
+#[derive(Debug, Clone, From)]
+pub struct EmailSuppression(domain::email_suppression::EmailSuppression);
+#[Object]
+impl EmailSuppression {
+    async fn id(&self) -> ID {
+        ID::from(self.0.id.as_str())
+    }
+
+    async fn email(&self) -> String {
+        self.0.email.to_string()
+    }
+
+    async fn reason(&self) -> Reason {
+        self.0.reason
+    }
+
+    async fn detail(&self) -> Option<String> {
+        self.0.detail.clone()
+    }
+
+    async fn created_at(&self) -> DateTime {
+        self.0.created_at.into()
+    }
+
+    async fn updated_at(&self) -> DateTime {
+        self.0.updated_at.into()
+    }
+}
+
+crate::define_item_payload!(EmailSuppressionPayload, EmailSuppression);
+crate::define_list_payload!(EmailSuppressionListPayload, EmailSuppression);
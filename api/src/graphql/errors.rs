@@ -50,6 +50,10 @@ impl Into<FieldError> for Error {
                 .msg
                 .clone()
                 .unwrap_or_else(|| "指定されたリソースは既に存在します".into()),
+            Suppressed => err
+                .msg
+                .clone()
+                .unwrap_or_else(|| "送信停止中のメールアドレスです".into()),
             Internal => "内部エラーが発生しました".into(),
         })
         .extend_with(|_, ext| {
@@ -61,6 +65,7 @@ impl Into<FieldError> for Error {
                     Forbidden => "FORBIDDEN",
                     NotFound => "NOT_FOUND",
                     Duplicate => "DUPLICATED",
+                    Suppressed => "SUPPRESSED",
                     Internal => "INTERNAL",
                 }
                 .to_string(),
@@ -11,7 +11,9 @@ use actix_web::http::header::HeaderValue;
 use app::AppResult;
 use app::adapter::UserAuth;
 use app::domain;
+use app::domain::api_key::Scope;
 use app::errors::Kind::BadRequest;
+use app::errors::Kind::Forbidden;
 use app::errors::Kind::Unauthorized;
 use app::errors::{AppError, NotFoundToNone};
 use async_graphql::{Context, EmptySubscription};
@@ -22,11 +24,14 @@ use tokio::sync::OnceCell;
 
 type AuthorizedUserId = domain::user::Id;
 type AuthorizedUser = OnceCell<domain::user::User>;
+type AuthorizedApiKey = domain::api_key::ApiKey;
 
 #[async_trait]
 trait AppContext {
     fn verified_user_id(&self) -> GraphResult<AuthorizedUserId>;
     async fn verified_user(&self) -> GraphResult<domain::user::User>;
+    fn verified_api_key(&self) -> GraphResult<AuthorizedApiKey>;
+    fn authorize(&self, scope: Scope) -> GraphResult<()>;
 }
 #[async_trait]
 impl<'a> AppContext for Context<'_> {
@@ -61,6 +66,29 @@ impl<'a> AppContext for Context<'_> {
         .await
         .cloned()
     }
+
+    fn verified_api_key(&self) -> GraphResult<AuthorizedApiKey> {
+        match self.data::<AppResult<AuthorizedApiKey>>()? {
+            Ok(v) => Ok(v.clone()),
+            Err(err) => Err(Unauthorized
+                .with(format!("authorization error: {}", err))
+                .into()),
+        }
+    }
+
+    // ユーザーとして認証済みであれば許可し、APIキーの場合はスコープを確認する
+    fn authorize(&self, scope: Scope) -> GraphResult<()> {
+        if self.verified_user_id().is_ok() {
+            return Ok(());
+        }
+        let api_key = self.verified_api_key()?;
+        if !api_key.has_scope(scope) {
+            return Err(Forbidden
+                .with(format!("scope {} is required", scope))
+                .into());
+        }
+        Ok(())
+    }
 }
 
 pub type Schema = async_graphql::Schema<QueryRoot, MutationRoot, EmptySubscription>;
@@ -69,6 +97,7 @@ pub type Schema = async_graphql::Schema<QueryRoot, MutationRoot, EmptySubscripti
 pub struct HttpHandler {
     schema: Schema,
     auth: Option<Arc<dyn UserAuth>>,
+    app: app::App,
 }
 
 impl HttpHandler {
@@ -78,7 +107,8 @@ impl HttpHandler {
 
         HttpHandler {
             schema,
-            auth: app.user_auth,
+            auth: app.user_auth.clone(),
+            app,
         }
     }
 
@@ -97,6 +127,11 @@ impl HttpHandler {
             }
         }
 
+        gql_req = gql_req.data(match headers.get("x-api-key") {
+            Some(hv) => verify_api_key(&self.app, hv).await,
+            None => Err(Unauthorized.into()),
+        });
+
         gql_req = gql_req.data(AuthorizedUser::new());
 
         self.schema.execute(gql_req).await.into()
@@ -113,3 +148,27 @@ async fn verify_token(auth: &dyn UserAuth, hv: &HeaderValue) -> AppResult<Author
     let uid = auth.verify(token_str).await?;
     Ok(uid)
 }
+
+async fn verify_api_key(app: &app::App, hv: &HeaderValue) -> AppResult<AuthorizedApiKey> {
+    let secret = hv.to_str().map_err(BadRequest.from_srcf())?;
+    let key_hash = domain::api_key::hash_secret(secret);
+
+    let api_key = app
+        .api_key_repository
+        .get_by_hash(app.db_session.conn(), &key_hash)
+        .await
+        .not_found_to_none()?
+        .ok_or_else(|| Unauthorized.with("invalid api key"))?;
+    if !api_key.is_active() {
+        return Err(Unauthorized.with("api key is expired or revoked"));
+    }
+
+    if api_key.should_touch() {
+        let api_key = api_key.clone().touch();
+        app.api_key_repository
+            .update(app.db_session.conn(), api_key)
+            .await?;
+    }
+
+    Ok(api_key)
+}
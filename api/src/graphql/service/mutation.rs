@@ -1,15 +1,30 @@
 use crate::graphql::GraphResult;
 use crate::graphql::service::AppContext;
 use crate::graphql::service::AppResult;
+use crate::graphql::service::types::asset::{Asset, AssetPayload};
+use crate::graphql::service::types::data_export::{DataExport, DataExportPayload};
+use crate::graphql::service::types::notification::{
+    Notification, NotificationPayload, NotificationPreference, NotificationPreferencePayload,
+};
 use crate::graphql::service::types::order::{Order, OrderPayload};
 use crate::graphql::service::types::user::{Me, MePayload};
-use crate::graphql::shared::types::{BoolPayload, Date};
+use crate::graphql::shared::types::{BoolPayload, Date, DateTime};
 use app::domain;
+use app::domain::device_token::Platform;
+use app::domain::notification::Category;
+use app::domain::notification::preference::QuietHours;
+use app::domain::types::asset_key::AssetKey;
+use app::domain::types::image_size::ImageSize;
+use app::domain::types::upload_policy::{UploadPolicy, UploadPurpose};
 use app::domain::user::Gender;
+use app::domain::user::data_export::Status as ExportStatus;
+use app::domain::user::deletion_request::Status as DeletionStatus;
 use app::errors::Kind::BadRequest;
+use app::errors::Kind::Duplicate;
 use app::errors::Kind::Internal;
-use async_graphql::{Context, Enum, InputObject, MergedObject, Object, SimpleObject};
-use rand::RngExt;
+use app::errors::Kind::NotFound;
+use app::errors::NotFoundToNone;
+use async_graphql::{Context, Enum, ID, InputObject, MergedObject, Object, SimpleObject};
 
 #[derive(MergedObject, Default)]
 pub struct MutationRoot(DefaultMutation);
@@ -18,45 +33,276 @@ pub struct MutationRoot(DefaultMutation);
 pub struct DefaultMutation;
 #[Object]
 impl DefaultMutation {
-    async fn pre_sign_upload(
+    async fn pre_sign_post_upload(
         &self,
         ctx: &Context<'_>,
-        input: PreSignUploadInput,
-    ) -> GraphResult<PreSignUploadPayload> {
+        input: PreSignPostUploadInput,
+    ) -> GraphResult<PreSignPostUploadPayload> {
+        let me = ctx.verified_user().await?;
+        let app = ctx.data::<app::App>()?;
+
+        let policy = UploadPolicy::new(
+            input.purpose,
+            input.content_type,
+            input.expires_in_secs.map(|v| v as u64),
+        )
+        .map_err(BadRequest.withf())?;
+
+        let asset = register_asset(app, &me, input.path, policy.content_type.clone()).await?;
+        let presigned = app
+            .storage
+            .presign_post_for_upload(&asset.upload_key, &policy)
+            .await?;
+        Ok(PreSignPostUploadPayload {
+            file_id: asset.id.to_string(),
+            key: asset.upload_key.to_string(),
+            url: presigned.url,
+            fields: presigned
+                .fields
+                .into_iter()
+                .map(|(name, value)| FormField { name, value })
+                .collect(),
+            max_size: policy.max_size,
+            expires_at: presigned.expires_at.into(),
+        })
+    }
+
+    async fn confirm_upload(&self, ctx: &Context<'_>, file_id: ID) -> GraphResult<AssetPayload> {
         let uid = ctx.verified_user_id()?;
         let app = ctx.data::<app::App>()?;
 
-        let file_id = base_62::encode(&rand::rng().random::<[u8; 16]>());
+        let asset = app
+            .asset_repository
+            .get(app.db_session.conn(), &file_id.0.into())
+            .await?;
+        if !asset.is_owned_by(&uid) {
+            return Err(NotFound.default().into());
+        }
+        // 確定後のエンキューに失敗した場合も、再度の確定で画像処理をやり直せるようにする
+        if asset.status == domain::asset::Status::Confirmed {
+            if asset.needs_processing() {
+                enqueue_process_image(app, &asset).await?;
+            }
+            return Ok(Asset::from(asset).into());
+        }
 
-        let key = format!("{}/{}/{}", input.path.path_string(), uid.as_str(), file_id);
-        let url = app
+        let head = app
             .storage
-            .presign_for_upload(&key.clone().try_into().map_err(Internal.withf())?)
+            .head_object(&asset.upload_key)
+            .await
+            .not_found_to_none()?
+            .ok_or_else(|| BadRequest.with("ファイルがアップロードされていません"))?;
+        if let (Some(expected), Some(actual)) = (&asset.content_type, &head.content_type) {
+            if expected != actual {
+                return Err(BadRequest.with("ファイル形式が一致しません").into());
+            }
+        }
+        if asset.needs_promotion() {
+            app.storage
+                .copy_object(&asset.upload_key, &asset.key)
+                .await?;
+        }
+        let asset = asset.confirm(head.content_type, head.content_length, head.e_tag);
+
+        let tx = app.db_session.begin_tx().await?;
+        app.asset_repository
+            .update(tx.conn(), asset.clone())
+            .await?;
+        tx.commit().await?;
+
+        // 確定前に消すと更新失敗時にファイルを失うため、コミット後に消す
+        // 失敗しても tmp/ 配下はバッチで掃除されるのでログに留める
+        if asset.needs_promotion() {
+            if let Err(err) = app.storage.delete_object(&asset.upload_key).await {
+                tracing::warn!("failed to delete upload {}: {:?}", asset.upload_key, err);
+            }
+        }
+
+        if asset.needs_processing() {
+            enqueue_process_image(app, &asset).await?;
+        }
+
+        Ok(Asset::from(asset).into())
+    }
+
+    async fn delete_asset(&self, ctx: &Context<'_>, file_id: ID) -> GraphResult<BoolPayload> {
+        let uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        let asset = app
+            .asset_repository
+            .get(app.db_session.conn(), &file_id.0.into())
             .await?;
-        Ok(PreSignUploadPayload {
-            file_id,
-            key,
-            url: url.to_string(),
+        if !asset.is_owned_by(&uid) {
+            return Err(NotFound.default().into());
+        }
+
+        let mut keys = vec![asset.key.clone(), asset.upload_key.clone()];
+        keys.extend(
+            [ImageSize::Large, ImageSize::Medium, ImageSize::Small]
+                .into_iter()
+                .map(|v| asset.key.variant_key(v)),
+        );
+        app.storage.delete_objects(&keys).await?;
+
+        let tx = app.db_session.begin_tx().await?;
+        app.asset_repository.delete(tx.conn(), &asset.id).await?;
+        tx.commit().await?;
+
+        // 変換済みの画像もCDNに残るため、キー配下をまとめて無効化する
+        // 削除自体は完了しているので、無効化の失敗はログに留める
+        if let Some(cdn) = &app.image_cdn {
+            if let Err(err) = cdn.invalidate(vec![format!("/{}*", asset.key)]).await {
+                tracing::error!("failed to invalidate cdn cache: {:?}", err);
+            }
+        }
+
+        Ok(true.into())
+    }
+
+    async fn issue_image_cookies(&self, ctx: &Context<'_>) -> GraphResult<ImageCookiesPayload> {
+        let uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+        let cdn = app
+            .image_cdn
+            .as_ref()
+            .ok_or_else(|| Internal.with("image cdn is not configured"))?;
+
+        // 本人のアセット配下に限定して発行する
+        let cookies = cdn.sign_cookies(&AssetKey::asset_prefix(&uid)).await?;
+        for header in cookies.to_set_cookie_headers() {
+            ctx.append_http_header("Set-Cookie", header);
+        }
+
+        Ok(ImageCookiesPayload {
+            cookies: cookies
+                .cookies
+                .into_iter()
+                .map(|(name, value)| Cookie { name, value })
+                .collect(),
+            expires_at: cookies.expires_at.into(),
         })
     }
 
+    // 同じトークンが別ユーザーで登録済みの場合はログイン中のユーザーに付け替える
+    async fn register_device_token(
+        &self,
+        ctx: &Context<'_>,
+        input: RegisterDeviceTokenInput,
+    ) -> GraphResult<BoolPayload> {
+        let uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        let token = input.token.trim().to_string();
+        if token.is_empty() || token.len() > DEVICE_TOKEN_MAX_LEN {
+            return Err(BadRequest.with("不正なデバイストークンです").into());
+        }
+        let device_token = domain::device_token::DeviceToken::new(uid, token, input.platform);
+
+        let tx = app.db_session.begin_tx().await?;
+        app.device_token_repository
+            .upsert(tx.conn(), device_token)
+            .await?;
+        tx.commit().await?;
+
+        Ok(true.into())
+    }
+
+    async fn unregister_device_token(
+        &self,
+        ctx: &Context<'_>,
+        token: String,
+    ) -> GraphResult<BoolPayload> {
+        let uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        let tx = app.db_session.begin_tx().await?;
+        let deleted = app
+            .device_token_repository
+            .delete_by_token(tx.conn(), &uid, token.trim())
+            .await?;
+        tx.commit().await?;
+
+        Ok((deleted > 0).into())
+    }
+
+    async fn mark_read(&self, ctx: &Context<'_>, id: ID) -> GraphResult<NotificationPayload> {
+        let uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        let tx = app.db_session.begin_tx().await?;
+        let notification = app
+            .notification_repository
+            .get(tx.conn(), &id.0.into())
+            .await?;
+        if !notification.is_owned_by(&uid) {
+            return Err(NotFound.default().into());
+        }
+        let notification = notification.mark_read();
+        app.notification_repository
+            .update(tx.conn(), notification.clone())
+            .await?;
+        tx.commit().await?;
+
+        Ok(Notification::from(notification).into())
+    }
+
+    async fn mark_all_read(&self, ctx: &Context<'_>) -> GraphResult<BoolPayload> {
+        let uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        let tx = app.db_session.begin_tx().await?;
+        app.notification_repository
+            .mark_all_read(tx.conn(), &uid)
+            .await?;
+        tx.commit().await?;
+
+        Ok(true.into())
+    }
+
+    async fn update_notification_preference(
+        &self,
+        ctx: &Context<'_>,
+        input: NotificationPreferenceInput,
+    ) -> GraphResult<NotificationPreferencePayload> {
+        let uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        let quiet_hours = input
+            .quiet_hours
+            .map(|v| QuietHours::new(v.start_hour, v.end_hour))
+            .transpose()
+            .map_err(BadRequest.withf())?;
+
+        let tx = app.db_session.begin_tx().await?;
+        let preference = app
+            .notification_preference_repository
+            .get_by_user(tx.conn(), &uid)
+            .await
+            .not_found_to_none()?
+            .unwrap_or_else(|| domain::notification::preference::Preference::new(uid.clone()))
+            .update(input.push_opt_outs, input.email_opt_outs, quiet_hours);
+        app.notification_preference_repository
+            .upsert(tx.conn(), preference.clone())
+            .await?;
+        tx.commit().await?;
+
+        Ok(NotificationPreference::from(preference).into())
+    }
+
     async fn call_async_task(&self, ctx: &Context<'_>) -> GraphResult<BoolPayload> {
         let app = ctx.data::<app::App>()?;
-        let payload = domain::types::task::AsyncTaskPayload {
+        let payload = domain::types::task::AsyncTaskPayload::Sample {
             name: "My Async Task".to_string(),
         };
-        app.sns_task_queue
-            .publish(
-                serde_json::to_value(&payload).map_err(Internal.from_srcf())?,
-                &app.env.sns_async_task_topic_arn,
-            )
-            .await?;
-        app.sqs_task_queue
-            .publish(
-                serde_json::to_value(&payload).map_err(Internal.from_srcf())?,
-                &app.env.sqs_async_task_queue_url,
-            )
-            .await?;
+        app::worker::publish_to_topic(
+            app,
+            payload.clone(),
+            domain::types::task::Priority::default(),
+            None,
+        )
+        .await?;
+        app::worker::enqueue(app, payload).await?;
         Ok(true.into())
     }
 
@@ -65,15 +311,13 @@ impl DefaultMutation {
         let payload = domain::types::task::SyncTaskPayload {
             name: "My Sync Task".to_string(),
         };
-        let resp_value = app
+        let resp = app
             .remote_function
-            .invoke(
-                serde_json::to_value(&payload).map_err(Internal.from_srcf())?,
+            .invoke::<_, domain::types::task::SyncTaskResponse>(
+                &payload,
                 &app.env.sync_task_lambda_arn,
             )
             .await?;
-        let resp: domain::types::task::SyncTaskResponse =
-            serde_json::from_value(resp_value).map_err(Internal.from_srcf())?;
         tracing::info!("Sync task response: {:?}", resp);
         Ok(true.into())
     }
@@ -127,12 +371,86 @@ impl DefaultMutation {
 
         let tx = app.db_session.begin_tx().await?;
         let user = app.user_repository.get(tx.conn(), &uid).await?;
-        app.user_repository.delete(tx.conn(), &user.id).await?;
-        tx.commit().await?;
+        let requests = app
+            .user_deletion_request_repository
+            .find_by_user(tx.conn(), &user.id)
+            .await?;
+        let request = match requests.into_iter().find(|v| v.is_in_progress()) {
+            // 前回の投入に失敗した可能性があるため、未着手のものは投入し直す
+            Some(request) if request.status == DeletionStatus::Pending => {
+                tx.commit().await?;
+                request
+            }
+            Some(_) => {
+                return Err(Duplicate.with("退会処理は既に受け付けています").into());
+            }
+            None => {
+                let request = domain::user::deletion_request::DeletionRequest::new(user.id.clone());
+                app.user_deletion_request_repository
+                    .insert(tx.conn(), request.clone())
+                    .await?;
+                tx.commit().await?;
+                request
+            }
+        };
+
+        // 投入し直しても一度しか実行されないよう、リクエストIDから冪等キーを決める
+        app::worker::enqueue_with_key(
+            app,
+            domain::types::task::AsyncTaskPayload::DeleteUser {
+                deletion_request_id: request.id.to_string(),
+            },
+            format!("delete-user-{}", request.id),
+        )
+        .await?;
 
         Ok(true.into())
     }
 
+    async fn request_data_export(&self, ctx: &Context<'_>) -> GraphResult<DataExportPayload> {
+        let uid = ctx.verified_user_id()?;
+        let app = ctx.data::<app::App>()?;
+
+        let tx = app.db_session.begin_tx().await?;
+        let user = app.user_repository.get(tx.conn(), &uid).await?;
+        let data_exports = app
+            .user_data_export_repository
+            .find_by_user(tx.conn(), &user.id)
+            .await?;
+        let data_export = match data_exports.into_iter().find(|v| v.is_in_progress()) {
+            // 前回の投入に失敗した可能性があるため、未着手のものは投入し直す
+            Some(data_export) if data_export.status == ExportStatus::Pending => {
+                tx.commit().await?;
+                data_export
+            }
+            Some(_) => {
+                return Err(Duplicate
+                    .with("データエクスポートは既に受け付けています")
+                    .into());
+            }
+            None => {
+                let data_export = domain::user::data_export::DataExport::new(user.id.clone());
+                app.user_data_export_repository
+                    .insert(tx.conn(), data_export.clone())
+                    .await?;
+                tx.commit().await?;
+                data_export
+            }
+        };
+
+        // 投入し直しても一度しか実行されないよう、エクスポートIDから冪等キーを決める
+        app::worker::enqueue_with_key(
+            app,
+            domain::types::task::AsyncTaskPayload::ExportUserData {
+                data_export_id: data_export.id.to_string(),
+            },
+            format!("export-{}", data_export.id),
+        )
+        .await?;
+
+        Ok(DataExport::from(data_export).into())
+    }
+
     async fn order_create(
         &self,
         ctx: &Context<'_>,
@@ -172,6 +490,27 @@ impl DefaultMutation {
     }
 }
 
+#[derive(InputObject)]
+struct NotificationPreferenceInput {
+    pub push_opt_outs: Vec<Category>,
+    pub email_opt_outs: Vec<Category>,
+    pub quiet_hours: Option<QuietHoursInput>,
+}
+
+#[derive(InputObject)]
+struct QuietHoursInput {
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+const DEVICE_TOKEN_MAX_LEN: usize = 4096;
+
+#[derive(InputObject)]
+struct RegisterDeviceTokenInput {
+    pub token: String,
+    pub platform: Platform,
+}
+
 #[derive(InputObject)]
 struct UserCreateInput {
     pub name: String,
@@ -197,29 +536,77 @@ struct OrderDetailCreateInput {
     pub quantity: u32,
 }
 
-#[derive(InputObject)]
-struct PreSignUploadInput {
-    pub path: PreSignUploadPath,
-}
-
 #[derive(Copy, Clone, Eq, PartialEq, Debug, Enum)]
 enum PreSignUploadPath {
     Asset,
     Temp,
 }
 
-impl PreSignUploadPath {
-    pub fn path_string(&self) -> String {
-        match self {
-            PreSignUploadPath::Asset => "asset".to_string(),
-            PreSignUploadPath::Temp => "tmp".to_string(),
-        }
-    }
+async fn register_asset(
+    app: &app::App,
+    user: &domain::user::User,
+    path: PreSignUploadPath,
+    content_type: String,
+) -> AppResult<domain::asset::Asset> {
+    let id = domain::asset::Id::generate();
+    let key = AssetKey::asset_key(user.id.clone(), id.to_string());
+    let upload_key = match path {
+        PreSignUploadPath::Asset => key.clone(),
+        PreSignUploadPath::Temp => AssetKey::temp_key(user.id.clone(), id.to_string()),
+    };
+    let asset = domain::asset::Asset::new(id, user.id.clone(), key, upload_key, Some(content_type));
+
+    let tx = app.db_session.begin_tx().await?;
+    app.asset_repository
+        .insert(tx.conn(), asset.clone())
+        .await?;
+    tx.commit().await?;
+
+    Ok(asset)
+}
+
+async fn enqueue_process_image(app: &app::App, asset: &domain::asset::Asset) -> AppResult<()> {
+    app::worker::enqueue(
+        app,
+        domain::types::task::AsyncTaskPayload::ProcessImage {
+            asset_id: asset.id.to_string(),
+        },
+    )
+    .await
+}
+
+#[derive(InputObject)]
+struct PreSignPostUploadInput {
+    pub path: PreSignUploadPath,
+    pub purpose: UploadPurpose,
+    pub content_type: String,
+    pub expires_in_secs: Option<u32>,
 }
 
 #[derive(SimpleObject)]
-struct PreSignUploadPayload {
+struct FormField {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(SimpleObject)]
+struct PreSignPostUploadPayload {
     pub file_id: String,
     pub key: String,
     pub url: String,
+    pub fields: Vec<FormField>,
+    pub max_size: u64,
+    pub expires_at: DateTime,
+}
+
+#[derive(SimpleObject)]
+struct Cookie {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(SimpleObject)]
+struct ImageCookiesPayload {
+    pub cookies: Vec<Cookie>,
+    pub expires_at: DateTime,
 }
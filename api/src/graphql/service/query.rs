@@ -3,9 +3,12 @@ use crate::graphql::data_loader::{OrderDataLoader, UserDataLoader};
 use crate::graphql::service::AppContext;
 use crate::graphql::service::types::order::{Order, OrderPayload};
 use crate::graphql::service::types::user::{Me, MePayload, User, UserListPayload, UserPayload};
+use app::domain::api_key::Scope;
+use app::domain::types::asset_key::AssetKey;
 use app::domain::types::image_size::ImageSize;
-use app::errors::Kind::BadRequest;
-use async_graphql::{Context, ID, MergedObject, Object};
+use app::domain::types::image_transform::{ImageFit, ImageFormat, ImageTransform};
+use app::errors::Kind::{BadRequest, Forbidden};
+use async_graphql::{Context, ID, InputObject, MergedObject, Object};
 
 #[derive(MergedObject, Default)]
 pub struct QueryRoot(DefaultQuery);
@@ -23,13 +26,25 @@ impl DefaultQuery {
         ctx: &Context<'_>,
         key: String,
         size: Option<ImageSize>,
+        transform: Option<ImageTransformInput>,
     ) -> GraphResult<String> {
-        let _uid = ctx.verified_user_id()?;
+        let uid = ctx.verified_user_id()?;
         let app = ctx.data::<app::App>()?;
-        let asset_key = key.try_into().map_err(BadRequest.withf())?;
+        let asset_key: AssetKey = key.try_into().map_err(BadRequest.withf())?;
+        if !asset_key.is_downloadable_by(&uid) {
+            return Err(Forbidden.default().into());
+        }
 
-        let presign_url = match (size, &app.image_cdn) {
-            (Some(size), Some(cdn)) => cdn.presign_for_get(&asset_key, size.into()).await?,
+        let transform = match (transform, size) {
+            (Some(v), _) => Some(
+                ImageTransform::new(v.width, v.height, v.fit, v.format, v.quality, v.dpr)
+                    .map_err(BadRequest.withf())?,
+            ),
+            (None, Some(size)) => Some(ImageTransform::from(size)),
+            (None, None) => None,
+        };
+        let presign_url = match (transform, &app.image_cdn) {
+            (Some(transform), Some(cdn)) => cdn.presign_for_get(&asset_key, &transform).await?,
             _ => app.storage.presign_for_get(&asset_key).await?,
         };
 
@@ -42,6 +57,7 @@ impl DefaultQuery {
     }
 
     async fn users(&self, ctx: &Context<'_>) -> GraphResult<UserListPayload> {
+        ctx.authorize(Scope::UserRead)?;
         let app = ctx.data::<app::App>()?;
         let conn = app.db_session.conn();
         let users = app.user_repository.find(conn).await?;
@@ -49,6 +65,7 @@ impl DefaultQuery {
     }
 
     async fn user(&self, ctx: &Context<'_>, id: ID) -> GraphResult<UserPayload> {
+        ctx.authorize(Scope::UserRead)?;
         let user_loader = ctx.data::<UserDataLoader>()?;
         let user = user_loader.load_one(id.0.into()).await?;
         let user = user.ok_or_else(|| BadRequest.with("user not found"))?;
@@ -56,9 +73,20 @@ impl DefaultQuery {
     }
 
     async fn order(&self, ctx: &Context<'_>, id: ID) -> GraphResult<OrderPayload> {
+        ctx.authorize(Scope::OrderRead)?;
         let order_loader = ctx.data::<OrderDataLoader>()?;
         let order = order_loader.load_one(id.0.into()).await?;
         let order = order.ok_or_else(|| BadRequest.with("order not found"))?;
         Ok(Order::from(order).into())
     }
 }
+
+#[derive(InputObject)]
+struct ImageTransformInput {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fit: Option<ImageFit>,
+    pub format: Option<ImageFormat>,
+    pub quality: Option<u8>,
+    pub dpr: Option<u8>,
+}
@@ -1,2 +1,5 @@
+pub mod asset;
+pub mod data_export;
+pub mod notification;
 pub mod order;
 pub mod user;
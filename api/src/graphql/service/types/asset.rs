@@ -0,0 +1,77 @@
+use crate::graphql::GraphResult;
+use crate::graphql::shared::types::DateTime;
+use app::domain;
+use app::domain::asset::Status;
+use app::domain::types::image_size::ImageSize;
+use async_graphql::{Context, ID, Object};
+use derive_more::From;
+
+#[derive(Debug, Clone, From)]
+pub struct Asset(domain::asset::Asset);
+#[Object]
+impl Asset {
+    async fn id(&self) -> ID {
+        ID::from(self.0.id.as_str())
+    }
+
+    async fn key(&self) -> String {
+        self.0.key.to_string()
+    }
+
+    async fn status(&self) -> Status {
+        self.0.status
+    }
+
+    async fn content_type(&self) -> Option<String> {
+        self.0.content_type.clone()
+    }
+
+    async fn size(&self) -> Option<u64> {
+        self.0.size
+    }
+
+    async fn checksum(&self) -> Option<String> {
+        self.0.checksum.clone()
+    }
+
+    async fn width(&self) -> Option<u32> {
+        self.0.width
+    }
+
+    async fn height(&self) -> Option<u32> {
+        self.0.height
+    }
+
+    // 画像処理が完了するまではnull
+    async fn variant_url(&self, ctx: &Context<'_>, size: ImageSize) -> GraphResult<Option<String>> {
+        if self.0.status != Status::Confirmed || self.0.width.is_none() {
+            return Ok(None);
+        }
+        let app = ctx.data::<app::App>()?;
+        let url = app
+            .storage
+            .presign_for_get(&self.0.key.variant_key(size))
+            .await?;
+        Ok(Some(url.to_string()))
+    }
+
+    // 画像はEXIF(GPS等)を落とすまではnull
+    async fn url(&self, ctx: &Context<'_>) -> GraphResult<Option<String>> {
+        if self.0.status != Status::Confirmed || self.0.needs_processing() {
+            return Ok(None);
+        }
+        let app = ctx.data::<app::App>()?;
+        let url = app.storage.presign_for_get(&self.0.key).await?;
+        Ok(Some(url.to_string()))
+    }
+
+    async fn created_at(&self) -> DateTime {
+        self.0.created_at.into()
+    }
+
+    async fn updated_at(&self) -> DateTime {
+        self.0.updated_at.into()
+    }
+}
+
+crate::define_item_payload!(AssetPayload, Asset);
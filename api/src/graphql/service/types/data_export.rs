@@ -0,0 +1,42 @@
+use crate::graphql::GraphResult;
+use crate::graphql::shared::types::DateTime;
+use app::domain;
+use app::domain::user::data_export::Status;
+use async_graphql::{Context, ID, Object};
+use derive_more::From;
+
+#[derive(Debug, Clone, From)]
+pub struct DataExport(domain::user::data_export::DataExport);
+#[Object]
+impl DataExport {
+    async fn id(&self) -> ID {
+        ID::from(self.0.id.as_str())
+    }
+
+    async fn status(&self) -> Status {
+        self.0.status
+    }
+
+    async fn download_url(&self, ctx: &Context<'_>) -> GraphResult<Option<String>> {
+        let Some(key) = &self.0.object_key else {
+            return Ok(None);
+        };
+        let app = ctx.data::<app::App>()?;
+        let url = app.storage.presign_for_get(key).await?;
+        Ok(Some(url.to_string()))
+    }
+
+    async fn completed_at(&self) -> Option<DateTime> {
+        self.0.completed_at.map(|v| v.into())
+    }
+
+    async fn created_at(&self) -> DateTime {
+        self.0.created_at.into()
+    }
+
+    async fn updated_at(&self) -> DateTime {
+        self.0.updated_at.into()
+    }
+}
+
+crate::define_item_payload!(DataExportPayload, DataExport);
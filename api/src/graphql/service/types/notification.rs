@@ -0,0 +1,77 @@
+use crate::graphql::shared::types::DateTime;
+use app::domain;
+use app::domain::notification::{Category, Type};
+use async_graphql::connection::Connection;
+use async_graphql::{ID, Json, Object, SimpleObject};
+use derive_more::From;
+use serde_json::Value;
+
+#[derive(Debug, Clone, From)]
+pub struct Notification(domain::notification::Notification);
+#[Object]
+impl Notification {
+    async fn id(&self) -> ID {
+        ID::from(self.0.id.as_str())
+    }
+
+    #[graphql(name = "type")]
+    async fn notification_type(&self) -> Type {
+        self.0.notification_type
+    }
+
+    async fn payload(&self) -> Json<Value> {
+        Json(self.0.payload.clone())
+    }
+
+    async fn is_read(&self) -> bool {
+        self.0.is_read()
+    }
+
+    async fn read_at(&self) -> Option<DateTime> {
+        self.0.read_at.map(|v| v.into())
+    }
+
+    async fn created_at(&self) -> DateTime {
+        self.0.created_at.into()
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct NotificationConnectionFields {
+    pub total_count: u64,
+    pub unread_count: u64,
+}
+
+// カーソルは先頭からのオフセット
+pub type NotificationConnection = Connection<usize, Notification, NotificationConnectionFields>;
+
+crate::define_item_payload!(NotificationPayload, Notification);
+
+#[derive(Debug, Clone, From)]
+pub struct NotificationPreference(domain::notification::preference::Preference);
+#[Object]
+impl NotificationPreference {
+    async fn push_opt_outs(&self) -> Vec<Category> {
+        self.0.push_opt_outs.clone()
+    }
+
+    async fn email_opt_outs(&self) -> Vec<Category> {
+        self.0.email_opt_outs.clone()
+    }
+
+    async fn quiet_hours(&self) -> Option<QuietHours> {
+        self.0.quiet_hours.map(|v| QuietHours {
+            start_hour: v.start_hour,
+            end_hour: v.end_hour,
+        })
+    }
+}
+
+// JSTの時(0-23)
+#[derive(SimpleObject)]
+pub struct QuietHours {
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+
+crate::define_item_payload!(NotificationPreferencePayload, NotificationPreference);
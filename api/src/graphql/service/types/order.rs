@@ -15,12 +15,14 @@ impl Order {
         ID::from(self.0.id.as_str())
     }
 
-    async fn user(&self, ctx: &Context<'_>) -> GraphResult<User> {
+    async fn user(&self, ctx: &Context<'_>) -> GraphResult<Option<User>> {
+        let Some(user_id) = self.0.user_id.clone() else {
+            return Ok(None);
+        };
         let user_loader = ctx.data::<UserDataLoader>()?;
-        let user_id = self.0.user_id.clone();
         let user = user_loader.load_one(user_id).await?;
         let user = user.ok_or_else(|| NotFound.with("user not found"))?;
-        Ok(User::from(user))
+        Ok(Some(User::from(user)))
     }
 
     async fn details(&self, ctx: &Context<'_>) -> GraphResult<Vec<OrderDetail>> {
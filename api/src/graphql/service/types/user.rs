@@ -1,8 +1,16 @@
 use crate::graphql::GraphResult;
+use crate::graphql::service::types::asset::Asset;
+use crate::graphql::service::types::data_export::DataExport;
+use crate::graphql::service::types::notification::{
+    Notification, NotificationConnection, NotificationConnectionFields, NotificationPreference,
+};
 use crate::graphql::service::types::order::Order;
 use crate::graphql::shared::types::enum_value::Gender;
 use crate::graphql::shared::types::{Date, DateTime};
 use app::domain;
+use app::errors::Kind::BadRequest;
+use app::errors::NotFoundToNone;
+use async_graphql::connection::{Connection, CursorType, Edge};
 use async_graphql::{Context, ID, Object};
 use derive_more::From;
 
@@ -33,6 +41,97 @@ impl Me {
         Ok(orders.into_iter().map(|v| v.into()).collect())
     }
 
+    async fn assets(&self, ctx: &Context<'_>) -> GraphResult<Vec<Asset>> {
+        let app = ctx.data::<app::App>()?;
+        let conn = app.db_session.conn();
+        let assets = app.asset_repository.find_by_user(conn, &self.0.id).await?;
+        Ok(assets.into_iter().map(|v| v.into()).collect())
+    }
+
+    async fn data_exports(&self, ctx: &Context<'_>) -> GraphResult<Vec<DataExport>> {
+        let app = ctx.data::<app::App>()?;
+        let conn = app.db_session.conn();
+        let data_exports = app
+            .user_data_export_repository
+            .find_by_user(conn, &self.0.id)
+            .await?;
+        Ok(data_exports.into_iter().map(|v| v.into()).collect())
+    }
+
+    async fn notifications(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        first: Option<i32>,
+    ) -> GraphResult<NotificationConnection> {
+        let app = ctx.data::<app::App>()?;
+        let offset = match after {
+            Some(cursor) => {
+                usize::decode_cursor(&cursor).map_err(|_| BadRequest.with("不正なカーソルです"))?
+                    + 1
+            }
+            None => 0,
+        };
+        let limit = match first {
+            Some(v) if v < 0 => {
+                return Err(BadRequest.with("件数は0以上で指定してください").into());
+            }
+            Some(v) => (v as usize).min(NOTIFICATIONS_MAX_LIMIT),
+            None => NOTIFICATIONS_DEFAULT_LIMIT,
+        };
+
+        let repository = &app.notification_repository;
+        // 次ページの有無を判定するため1件多く取得する
+        let notifications = repository
+            .find_by_user(
+                app.db_session.conn(),
+                &self.0.id,
+                offset as u64,
+                limit as u64 + 1,
+            )
+            .await?;
+        let total_count = repository
+            .count_by_user(app.db_session.conn(), &self.0.id)
+            .await?;
+        let unread_count = repository
+            .count_unread_by_user(app.db_session.conn(), &self.0.id)
+            .await?;
+
+        let mut connection = Connection::with_additional_fields(
+            offset > 0,
+            notifications.len() > limit,
+            NotificationConnectionFields {
+                total_count,
+                unread_count,
+            },
+        );
+        connection.edges.extend(
+            notifications
+                .into_iter()
+                .take(limit)
+                .enumerate()
+                .map(|(i, v)| Edge::new(offset + i, Notification::from(v))),
+        );
+        Ok(connection)
+    }
+
+    // 未設定の場合は全て受信する既定値を返す
+    async fn notification_preference(
+        &self,
+        ctx: &Context<'_>,
+    ) -> GraphResult<NotificationPreference> {
+        let app = ctx.data::<app::App>()?;
+        let preference = app
+            .notification_preference_repository
+            .get_by_user(app.db_session.conn(), &self.0.id)
+            .await
+            .not_found_to_none()?
+            .unwrap_or_else(|| {
+                domain::notification::preference::Preference::new(self.0.id.clone())
+            });
+        Ok(preference.into())
+    }
+
     async fn created_at(&self) -> DateTime {
         self.0.created_at.into()
     }
@@ -42,6 +141,9 @@ impl Me {
     }
 }
 
+const NOTIFICATIONS_DEFAULT_LIMIT: usize = 20;
+const NOTIFICATIONS_MAX_LIMIT: usize = 100;
+
 #[derive(Debug, Clone, From)]
 pub struct User(domain::user::User);
 #[Object]
@@ -0,0 +1,38 @@
+use actix_web::HttpResponse;
+use actix_web::web::{Data, Path, Query};
+use app::adapter::MailOutbox;
+use serde::Deserialize;
+use std::sync::Arc;
+
+// ローカル用Mailが書き出したメッセージを確認する
+#[derive(Deserialize)]
+pub struct ShowQuery {
+    format: Option<String>,
+}
+
+pub async fn index(outbox: Data<Arc<dyn MailOutbox>>) -> HttpResponse {
+    HttpResponse::Ok().json(outbox.list())
+}
+
+pub async fn show(
+    outbox: Data<Arc<dyn MailOutbox>>,
+    id: Path<String>,
+    query: Query<ShowQuery>,
+) -> HttpResponse {
+    let Some(mail) = outbox.get(&id) else {
+        return HttpResponse::NotFound().finish();
+    };
+    match (query.format.as_deref(), mail.html) {
+        (Some("text"), _) | (_, None) => HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(format!("Subject: {}\n\n{}", mail.subject, mail.text)),
+        (_, Some(html)) => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(html),
+    }
+}
+
+pub async fn clear(outbox: Data<Arc<dyn MailOutbox>>) -> HttpResponse {
+    outbox.clear();
+    HttpResponse::NoContent().finish()
+}
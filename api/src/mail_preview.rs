@@ -0,0 +1,54 @@
+use actix_web::HttpResponse;
+use actix_web::web::{Path, Query};
+use app::mail_template::{self, Locale, TemplateId};
+use serde::Deserialize;
+use std::str::FromStr;
+
+// 開発者向けにメールテンプレートをサンプル値で描画する
+#[derive(Deserialize)]
+pub struct PreviewQuery {
+    locale: Option<String>,
+    format: Option<String>,
+}
+
+pub async fn index() -> HttpResponse {
+    let links = mail_template::template_ids()
+        .into_iter()
+        .map(|id| {
+            format!(
+                r#"<li>{id} <a href="preview/{id}?locale=ja">ja</a> <a href="preview/{id}?locale=en">en</a> <a href="preview/{id}?locale=ja&format=text">text</a></li>"#
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("");
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(format!("<ul>{}</ul>", links))
+}
+
+pub async fn show(template_id: Path<String>, query: Query<PreviewQuery>) -> HttpResponse {
+    let Ok(template_id) = TemplateId::from_str(&template_id) else {
+        return HttpResponse::NotFound().finish();
+    };
+    let locale = query
+        .locale
+        .as_deref()
+        .and_then(|v| Locale::from_str(v).ok())
+        .unwrap_or_default();
+
+    match mail_template::preview(template_id, locale) {
+        Ok(rendered) if query.format.as_deref() == Some("text") => HttpResponse::Ok()
+            .content_type("text/plain; charset=utf-8")
+            .body(format!(
+                "Subject: {}\n\n{}",
+                rendered.subject, rendered.text
+            )),
+        Ok(rendered) => HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(rendered.html),
+        Err(err) => {
+            tracing::error!("{:?}", err);
+            HttpResponse::InternalServerError().body(format!("{:?}", err))
+        }
+    }
+}
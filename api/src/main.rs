@@ -1,5 +1,8 @@
 mod graphql;
+mod mail_outbox;
+mod mail_preview;
 mod playground;
+mod storage;
 
 use crate::playground::my_playground_source;
 use actix_cors::Cors;
@@ -23,6 +26,9 @@ async fn main() -> std::io::Result<()> {
     let user_api_handler = graphql::service::HttpHandler::new(app.clone()).await;
     let admin_api_handler = graphql::admin::HttpHandler::new(app.clone()).await;
     let port = app.env.port.clone();
+    let storage_app = app.storage_url_signer.is_some().then(|| app.clone());
+    let serves_mail_preview = !app.env.is_prod();
+    let mail_outbox = app.mail_outbox.clone();
 
     let app_factory = move || {
         let mut app = App::new()
@@ -30,7 +36,7 @@ async fn main() -> std::io::Result<()> {
                 Cors::default()
                     .allow_any_origin()
                     .allow_any_header()
-                    .allowed_methods(["GET", "POST"])
+                    .allowed_methods(["GET", "POST", "PUT", "DELETE"])
                     .max_age(3600)
                     .supports_credentials(),
             )
@@ -59,6 +65,51 @@ async fn main() -> std::io::Result<()> {
             );
         }
 
+        if serves_mail_preview {
+            app = app
+                .service(
+                    web::resource("/api/mail/preview")
+                        .guard(guard::Get())
+                        .to(mail_preview::index),
+                )
+                .service(
+                    web::resource("/api/mail/preview/{template_id}")
+                        .guard(guard::Get())
+                        .to(mail_preview::show),
+                );
+        }
+
+        // ローカル用Mailの送信履歴
+        if let Some(mail_outbox) = mail_outbox.clone() {
+            app = app
+                .app_data(Data::new(mail_outbox))
+                .service(
+                    web::resource("/api/mail/outbox")
+                        .route(web::get().to(mail_outbox::index))
+                        .route(web::delete().to(mail_outbox::clear)),
+                )
+                .service(
+                    web::resource("/api/mail/outbox/{id}")
+                        .guard(guard::Get())
+                        .to(mail_outbox::show),
+                );
+        }
+
+        // ローカル用Storageの署名付きURLを受け付ける
+        if let Some(storage_app) = storage_app.clone() {
+            app = app
+                .app_data(Data::new(storage_app))
+                .service(
+                    web::resource("/api/storage")
+                        .guard(guard::Post())
+                        .to(storage::post_object),
+                )
+                .service(
+                    web::resource("/api/storage/{key:.*}")
+                        .route(web::get().to(storage::get_object)),
+                );
+        }
+
         app
     };
 
@@ -0,0 +1,181 @@
+use actix_multipart::Multipart;
+use actix_web::http::StatusCode;
+use actix_web::web::{Bytes, Data, Path, Query};
+use actix_web::{HttpRequest, HttpResponse};
+use app::AppResult;
+use app::adapter::{ByteRange, SignedOperation, SignedRequest, UrlSigner};
+use app::domain::types::asset_key::AssetKey;
+use app::errors::AppError;
+use app::errors::Kind::*;
+use futures_util::StreamExt;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+// ローカル用Storageが発行した署名付きURLの受け口。S3のGETとPOSTアップロードを模している
+#[derive(Deserialize)]
+pub struct SignatureQuery {
+    expires: i64,
+    signature: String,
+}
+
+pub async fn get_object(
+    app: Data<app::App>,
+    http_req: HttpRequest,
+    key: Path<String>,
+    query: Query<SignatureQuery>,
+) -> HttpResponse {
+    let range = http_req
+        .headers()
+        .get("range")
+        .and_then(|v| v.to_str().ok())
+        .and_then(ByteRange::parse_header);
+    to_response(get(&app, key.into_inner(), query.into_inner(), range).await)
+}
+
+pub async fn post_object(app: Data<app::App>, payload: Multipart) -> HttpResponse {
+    to_response(post(&app, payload).await)
+}
+
+async fn get(
+    app: &app::App,
+    key: String,
+    query: SignatureQuery,
+    range: Option<ByteRange>,
+) -> AppResult<HttpResponse> {
+    let key = verify(app, SignedOperation::Get, key, query)?;
+    let object = app.storage.get_object_stream(&key, range).await?;
+
+    let mut res = match &object.content_range {
+        Some(v) => {
+            let mut res = HttpResponse::PartialContent();
+            res.insert_header(("content-range", v.as_str()));
+            res
+        }
+        None => HttpResponse::Ok(),
+    };
+    Ok(res
+        .content_type(
+            object
+                .content_type
+                .unwrap_or(DEFAULT_CONTENT_TYPE.to_string()),
+        )
+        .insert_header(("accept-ranges", "bytes"))
+        .no_chunking(object.content_length)
+        .streaming(object.body))
+}
+
+async fn post(app: &app::App, mut payload: Multipart) -> AppResult<HttpResponse> {
+    // S3と同様に file フィールドは最後に送られる前提で、それまでのフィールドを保持する
+    let mut fields = HashMap::new();
+    let mut file = None;
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(|e| BadRequest.with(e.to_string()))?;
+        let name = field.name().unwrap_or_default().to_string();
+        let max_size = fields
+            .get("x-max-size")
+            .and_then(|v: &String| v.parse::<u64>().ok());
+
+        let mut data = vec![];
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| BadRequest.with(e.to_string()))?;
+            data.extend_from_slice(&chunk);
+            if name == "file" && max_size.map(|v| data.len() as u64 > v).unwrap_or(true) {
+                return Err(BadRequest.with("ファイルサイズが上限を超えています"));
+            }
+        }
+        if name == "file" {
+            file = Some(Bytes::from(data));
+            break;
+        }
+        fields.insert(
+            name,
+            String::from_utf8(data).map_err(BadRequest.from_srcf())?,
+        );
+    }
+
+    let field = |name: &str| {
+        fields
+            .get(name)
+            .cloned()
+            .ok_or_else(|| BadRequest.with(format!("{} がありません", name)))
+    };
+    let parse = |name: &str| {
+        field(name)?
+            .parse::<u64>()
+            .map_err(|_| BadRequest.with(format!("{} が不正です", name)))
+    };
+    let file = file.ok_or_else(|| BadRequest.with("file がありません"))?;
+    let content_type = field("Content-Type")?;
+    let min_size = parse("x-min-size")?;
+    let max_size = parse("x-max-size")?;
+    let key = verify(
+        app,
+        SignedOperation::Post {
+            content_type: content_type.clone(),
+            min_size,
+            max_size,
+        },
+        field("key")?,
+        SignatureQuery {
+            expires: field("x-expires")?
+                .parse()
+                .map_err(|_| BadRequest.with("x-expires が不正です"))?,
+            signature: field("x-signature")?,
+        },
+    )?;
+
+    let size = file.len() as u64;
+    if size < min_size || size > max_size {
+        return Err(BadRequest.with("ファイルサイズが許可された範囲外です"));
+    }
+    app.storage.put_object(&key, file, &content_type).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+fn verify(
+    app: &app::App,
+    operation: SignedOperation,
+    key: String,
+    query: SignatureQuery,
+) -> AppResult<AssetKey> {
+    let signer: &dyn UrlSigner = app
+        .storage_url_signer
+        .as_deref()
+        .ok_or_else(|| NotFound.default())?;
+    let key: AssetKey = key.try_into().map_err(BadRequest.withf())?;
+    signer.verify(
+        &SignedRequest {
+            operation,
+            key: key.clone(),
+            expires: query.expires,
+        },
+        &query.signature,
+    )?;
+    Ok(key)
+}
+
+fn to_response(res: AppResult<HttpResponse>) -> HttpResponse {
+    match res {
+        Ok(v) => v,
+        Err(err) => {
+            if err.kind == Internal {
+                tracing::error!("{:?}", err);
+            }
+            HttpResponse::build(status_code(&err)).body(err.msg.unwrap_or_default())
+        }
+    }
+}
+
+fn status_code(err: &AppError) -> StatusCode {
+    match err.kind {
+        BadRequest => StatusCode::BAD_REQUEST,
+        Unauthorized => StatusCode::UNAUTHORIZED,
+        Forbidden => StatusCode::FORBIDDEN,
+        NotFound => StatusCode::NOT_FOUND,
+        Duplicate => StatusCode::CONFLICT,
+        Suppressed => StatusCode::UNPROCESSABLE_ENTITY,
+        Internal => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
@@ -1,11 +1,21 @@
 use crate::domain::admin_user;
 use crate::domain::types::asset_key::AssetKey;
 use crate::domain::types::email::Email;
-use crate::domain::types::image_size::ImageSize;
+use crate::domain::types::image_transform::ImageTransform;
+use crate::domain::types::outgoing_mail::OutgoingMail;
+use crate::domain::types::task::TaskAttributes;
+use crate::domain::types::time::LocalDateTime;
+use crate::domain::types::upload_policy::UploadPolicy;
 use crate::errors::AppError;
+use crate::errors::Kind::Internal;
+pub use crate::infra::local_storage::types::{SignedOperation, SignedRequest};
 pub use crate::infra::rdb::session_manager::TransactionGuard;
-pub use crate::infra::s3::types::HeadObjectResponse;
+pub use crate::infra::s3::types::{
+    BodyStream, ByteRange, CompletedPart, HeadObjectResponse, MultipartUpload, ObjectPage,
+    ObjectStream, ObjectSummary, PresignedPost,
+};
 pub use crate::infra::sentry::InitGuard as ErrorNotifierGuard;
+use crate::mail_template::{self, TemplateContext, TemplateId};
 use crate::{AppResult, domain};
 use async_trait::async_trait;
 use bytes::Bytes;
@@ -13,26 +23,281 @@ use http::Uri;
 use once_cell::sync::OnceCell;
 pub use sea_orm::DatabaseConnection;
 use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 
 #[async_trait]
 pub trait Storage: Send + Sync {
-    async fn presign_for_upload(&self, key: &AssetKey) -> AppResult<Uri>;
+    async fn presign_post_for_upload(
+        &self,
+        key: &AssetKey,
+        policy: &UploadPolicy,
+    ) -> AppResult<PresignedPost>;
     async fn presign_for_get(&self, key: &AssetKey) -> AppResult<Uri>;
     async fn download_object(&self, key: &AssetKey) -> AppResult<Bytes>;
+    async fn get_object_stream(
+        &self,
+        key: &AssetKey,
+        range: Option<ByteRange>,
+    ) -> AppResult<ObjectStream>;
+    async fn put_object(&self, key: &AssetKey, body: Bytes, content_type: &str) -> AppResult<()>;
+    async fn create_multipart_upload(
+        &self,
+        key: &AssetKey,
+        content_type: &str,
+    ) -> AppResult<MultipartUpload>;
+    async fn upload_part(
+        &self,
+        upload: &MultipartUpload,
+        part_number: i32,
+        body: Bytes,
+    ) -> AppResult<CompletedPart>;
+    async fn complete_multipart_upload(
+        &self,
+        upload: &MultipartUpload,
+        parts: Vec<CompletedPart>,
+    ) -> AppResult<()>;
+    async fn abort_multipart_upload(&self, upload: &MultipartUpload) -> AppResult<()>;
     async fn head_object(&self, key: &AssetKey) -> AppResult<HeadObjectResponse>;
     async fn copy_object(&self, src_key: &AssetKey, dest_key: &AssetKey) -> AppResult<()>;
     async fn delete_object(&self, key: &AssetKey) -> AppResult<()>;
+    async fn list_objects(
+        &self,
+        prefix: &str,
+        continuation_token: Option<String>,
+    ) -> AppResult<ObjectPage>;
+    async fn list_object_keys(&self, prefix: &str) -> AppResult<Vec<AssetKey>>;
+    async fn delete_objects(&self, keys: &[AssetKey]) -> AppResult<u64>;
+    async fn delete_objects_by_prefix(&self, prefix: &str) -> AppResult<u64>;
+}
+
+// S3のマルチパートアップロードは最終パート以外5MiB以上である必要がある
+const MULTIPART_PART_SIZE: usize = 8 * 1024 * 1024;
+
+// サーバーで生成する大きなファイルを、全体をメモリに持たずにパート単位でアップロードする
+pub struct MultipartWriter<'a> {
+    storage: &'a dyn Storage,
+    upload: MultipartUpload,
+    buffer: Vec<u8>,
+    parts: Vec<CompletedPart>,
+    size: u64,
+}
+impl<'a> MultipartWriter<'a> {
+    pub async fn new(
+        storage: &'a dyn Storage,
+        key: &AssetKey,
+        content_type: &str,
+    ) -> AppResult<Self> {
+        let upload = storage.create_multipart_upload(key, content_type).await?;
+        Ok(Self {
+            storage,
+            upload,
+            buffer: vec![],
+            parts: vec![],
+            size: 0,
+        })
+    }
+
+    pub async fn write(&mut self, data: &[u8]) -> AppResult<()> {
+        self.buffer.extend_from_slice(data);
+        self.size += data.len() as u64;
+        while self.buffer.len() >= MULTIPART_PART_SIZE {
+            let rest = self.buffer.split_off(MULTIPART_PART_SIZE);
+            let part = std::mem::replace(&mut self.buffer, rest);
+            self.upload_part(part).await?;
+        }
+        Ok(())
+    }
+
+    // 書き込んだ合計バイト数を返す。完了できなかった場合はアップロードを中止する
+    pub async fn finish(mut self) -> AppResult<u64> {
+        if let Err(err) = self.complete().await {
+            if let Err(abort_err) = self.storage.abort_multipart_upload(&self.upload).await {
+                tracing::warn!("failed to abort multipart upload: {:?}", abort_err);
+            }
+            return Err(err);
+        }
+        Ok(self.size)
+    }
+
+    pub async fn abort(self) -> AppResult<()> {
+        self.storage.abort_multipart_upload(&self.upload).await
+    }
+
+    async fn complete(&mut self) -> AppResult<()> {
+        if !self.buffer.is_empty() || self.parts.is_empty() {
+            let part = std::mem::take(&mut self.buffer);
+            self.upload_part(part).await?;
+        }
+        self.storage
+            .complete_multipart_upload(&self.upload, std::mem::take(&mut self.parts))
+            .await
+    }
+
+    async fn upload_part(&mut self, data: Vec<u8>) -> AppResult<()> {
+        let part_number = self.parts.len() as i32 + 1;
+        let part = self
+            .storage
+            .upload_part(&self.upload, part_number, Bytes::from(data))
+            .await?;
+        self.parts.push(part);
+        Ok(())
+    }
+}
+
+// ローカル用Storageが発行した署名付きURLを検証する
+pub trait UrlSigner: Send + Sync {
+    fn verify(&self, request: &SignedRequest, signature: &str) -> AppResult<()>;
 }
 
 #[async_trait]
 pub trait TaskQueue: Send + Sync {
     async fn publish(&self, input: serde_json::Value, target: &str) -> AppResult<()>;
+    // SNSではサブスクリプションのフィルターポリシーで属性を参照できる
+    async fn publish_with_attributes(
+        &self,
+        input: serde_json::Value,
+        target: &str,
+        attributes: &TaskAttributes,
+    ) -> AppResult<()>;
+    // delay は MAX_TASK_DELAY まで
+    async fn publish_delayed(
+        &self,
+        input: serde_json::Value,
+        target: &str,
+        delay: Duration,
+    ) -> AppResult<()>;
+    // FIFOキュー・トピック向け。重複排除IDが同じメッセージは5分間は一度しか配信されない
+    async fn publish_fifo(
+        &self,
+        input: serde_json::Value,
+        target: &str,
+        group_id: &str,
+        deduplication_id: &str,
+    ) -> AppResult<()>;
+    // 10件ずつに分けて送信し、一時的に失敗したエントリはリトライする
+    async fn publish_batch(
+        &self,
+        entries: Vec<BatchEntry>,
+        target: &str,
+    ) -> AppResult<BatchPublishResult>;
+}
+// SQSのDelaySecondsの上限
+pub const MAX_TASK_DELAY: Duration = Duration::from_secs(15 * 60);
+// SendMessageBatch / PublishBatch の1回あたりの上限
+pub const MAX_PUBLISH_BATCH_SIZE: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct BatchEntry {
+    pub input: serde_json::Value,
+    // FIFOキュー・トピック向け
+    pub group_id: Option<String>,
+    pub deduplication_id: Option<String>,
+    pub attributes: Option<TaskAttributes>,
+}
+impl BatchEntry {
+    pub fn new(input: serde_json::Value) -> Self {
+        Self {
+            input,
+            group_id: None,
+            deduplication_id: None,
+            attributes: None,
+        }
+    }
+
+    pub fn fifo(input: serde_json::Value, group_id: &str, deduplication_id: &str) -> Self {
+        Self {
+            input,
+            group_id: Some(group_id.to_string()),
+            deduplication_id: Some(deduplication_id.to_string()),
+            attributes: None,
+        }
+    }
+
+    pub fn attributes(self, attributes: TaskAttributes) -> Self {
+        Self {
+            attributes: Some(attributes),
+            ..self
+        }
+    }
+}
+#[derive(Debug, Clone, Default)]
+pub struct BatchPublishResult {
+    pub success_count: usize,
+    // リトライしても送信できなかったエントリ
+    pub failures: Vec<BatchPublishFailure>,
+}
+impl BatchPublishResult {
+    pub fn is_all_succeeded(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+#[derive(Debug, Clone)]
+pub struct BatchPublishFailure {
+    // 入力の entries での位置
+    pub index: usize,
+    pub code: String,
+    pub message: Option<String>,
+    // 入力が不正な場合は true になり、リトライしない
+    pub sender_fault: bool,
+}
+
+#[async_trait]
+pub trait QueueReader: Send + Sync {
+    // 受信したメッセージは visibility_timeout の間は他の受信者から見えなくなる。max_messages は10まで
+    // 空で返った場合はキューが空であることを表す
+    async fn receive(
+        &self,
+        target: &str,
+        max_messages: i32,
+        visibility_timeout: Duration,
+    ) -> AppResult<Vec<ReceivedMessage>>;
+    async fn delete(&self, target: &str, receipt_handle: &str) -> AppResult<()>;
+    // 可視性タイムアウトを解除し、すぐに再受信できるようにする
+    async fn release(&self, target: &str, receipt_handle: &str) -> AppResult<()>;
+}
+#[derive(Debug, Clone)]
+pub struct ReceivedMessage {
+    pub message_id: String,
+    // 削除・解除に使う。受信のたびに変わる
+    pub receipt_handle: String,
+    pub body: String,
+    pub receive_count: u32,
+    pub sent_at: Option<LocalDateTime>,
 }
 
 #[async_trait]
 pub trait RemoteFunction: Send + Sync {
-    async fn invoke(&self, input: serde_json::Value, arn: &str) -> AppResult<serde_json::Value>;
+    // 関数の完了を待って結果を返す。関数内のエラーも Err になる
+    // タイムアウトで Err になっても関数は実行を続けているため、再実行してよい処理にだけ使う
+    async fn invoke_json(
+        &self,
+        input: serde_json::Value,
+        arn: &str,
+    ) -> AppResult<serde_json::Value>;
+    // 受け付けられた時点で返り、関数の完了は待たない
+    async fn invoke_event(&self, input: serde_json::Value, arn: &str) -> AppResult<()>;
+}
+impl dyn RemoteFunction {
+    pub async fn invoke<Req, Resp>(&self, input: &Req, arn: &str) -> AppResult<Resp>
+    where
+        Req: Serialize + Sync + ?Sized,
+        Resp: DeserializeOwned,
+    {
+        let input = serde_json::to_value(input).map_err(Internal.from_srcf())?;
+        let output = self.invoke_json(input, arn).await?;
+        serde_json::from_value(output).map_err(Internal.from_srcf())
+    }
+
+    pub async fn invoke_async<Req>(&self, input: &Req, arn: &str) -> AppResult<()>
+    where
+        Req: Serialize + Sync + ?Sized,
+    {
+        let input = serde_json::to_value(input).map_err(Internal.from_srcf())?;
+        self.invoke_event(input, arn).await
+    }
 }
 
 #[async_trait]
@@ -146,12 +411,120 @@ impl UserPrincipal {
 
 #[async_trait]
 pub trait ImageCdn: Send + Sync {
-    async fn presign_for_get(&self, key: &AssetKey, size: ImageSize) -> AppResult<Uri>;
+    async fn presign_for_get(&self, key: &AssetKey, transform: &ImageTransform) -> AppResult<Uri>;
+    async fn sign_cookies(&self, path_prefix: &str) -> AppResult<SignedCookies>;
+    // パスは "/asset/xxx/*" のように先頭スラッシュ付きで指定する
+    async fn invalidate(&self, paths: Vec<String>) -> AppResult<()>;
+}
+// path_prefix 配下の画像をまとめて閲覧できるCloudFrontの署名付きCookie
+#[derive(Debug, Clone)]
+pub struct SignedCookies {
+    pub cookies: Vec<(String, String)>,
+    pub domain: Option<String>,
+    pub path: String,
+    pub expires_at: LocalDateTime,
+}
+impl SignedCookies {
+    pub fn to_set_cookie_headers(&self) -> Vec<String> {
+        let expires = self
+            .expires_at
+            .with_timezone(&chrono::Utc)
+            .format("%a, %d %b %Y %H:%M:%S GMT");
+        self.cookies
+            .iter()
+            .map(|(name, value)| {
+                let mut header = format!(
+                    "{}={}; Path={}; Expires={}; Secure; HttpOnly; SameSite=None",
+                    name, value, self.path, expires
+                );
+                if let Some(domain) = &self.domain {
+                    header.push_str(&format!("; Domain={}", domain));
+                }
+                header
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
 pub trait Mail: Send + Sync {
-    async fn send_text(&self, to: Email, subject: &str, text: &str) -> AppResult<()>;
+    async fn send(&self, mail: OutgoingMail) -> AppResult<()>;
+
+    async fn send_text(&self, to: Email, subject: &str, text: &str) -> AppResult<()> {
+        self.send(OutgoingMail::new(subject, text).to(to)).await
+    }
+
+    async fn send_template(
+        &self,
+        to: Email,
+        template_id: TemplateId,
+        context: TemplateContext,
+    ) -> AppResult<()> {
+        let rendered = mail_template::render(template_id, &context)?;
+        self.send(OutgoingMail::from(rendered).to(to)).await
+    }
+}
+
+// ローカル用Mailが送信の代わりに保存したメッセージを参照する
+pub trait MailOutbox: Send + Sync {
+    fn list(&self) -> Vec<CapturedMail>;
+    fn get(&self, id: &str) -> Option<CapturedMail>;
+    fn clear(&self);
+}
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CapturedMail {
+    pub id: String,
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    pub bcc: Vec<String>,
+    pub subject: String,
+    pub text: String,
+    pub html: Option<String>,
+    pub attachments: Vec<String>,
+    pub path: String,
+    pub sent_at: String,
+}
+
+#[async_trait]
+pub trait PushNotifier: Send + Sync {
+    async fn send_multicast(
+        &self,
+        tokens: Vec<String>,
+        message: &PushMessage,
+    ) -> AppResult<MulticastResult>;
+    // topic は "/topics/" を付けずに指定する
+    // ユーザーごとの受信設定・おやすみ時間を確認できないため、ユーザー向けのお知らせには使わず
+    // カテゴリを持つ通知は push::send_to_user で送る
+    async fn send_to_topic(&self, topic: &str, message: &PushMessage) -> AppResult<()>;
+}
+#[derive(Debug, Clone, Default)]
+pub struct PushMessage {
+    pub title: String,
+    pub body: String,
+    pub image_url: Option<String>,
+    pub data: HashMap<String, String>,
+}
+impl PushMessage {
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn data(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.data.insert(key.into(), value.into());
+        self
+    }
+}
+#[derive(Debug, Clone, Default)]
+pub struct MulticastResult {
+    pub success_count: usize,
+    pub failure_count: usize,
+    // アンインストール等で無効になったトークン。呼び出し側で削除する
+    pub unregistered_tokens: Vec<String>,
 }
 
 pub trait ErrorNotifier: Send + Sync {
@@ -0,0 +1,62 @@
+mod cleanup_processed_tasks;
+mod cleanup_tmp_uploads;
+mod dispatch_scheduled_tasks;
+
+use crate::domain::types::time::now;
+use crate::errors::Kind::Internal;
+use crate::{App, AppResult};
+use serde_json::{Map, Value, json};
+
+// batch_fn から定期実行されるジョブ
+// 1つのジョブが失敗しても残りのジョブは実行し、失敗したジョブをまとめてエラーにする
+pub async fn run(app: &App) -> AppResult<()> {
+    let results = [
+        (
+            "DispatchScheduledTasks",
+            dispatch_scheduled_tasks::exec(app).await,
+        ),
+        ("CleanupTmpUploads", cleanup_tmp_uploads::exec(app).await),
+        (
+            "CleanupProcessedTasks",
+            cleanup_processed_tasks::exec(app).await,
+        ),
+    ];
+
+    let mut failed = vec![];
+    for (job, result) in results {
+        if let Err(err) = &result {
+            tracing::error!("batch job {} failed: {:?}", job, err);
+            failed.push(job);
+        }
+        emit_metrics(job, &[("Failures", "Count", result.is_err() as u64)]);
+    }
+    if !failed.is_empty() {
+        return Err(Internal.with(format!("batch jobs failed: {}", failed.join(", "))));
+    }
+    Ok(())
+}
+
+// CloudWatch Embedded Metric Format で標準出力に書き出すとメトリクスとして取り込まれる
+// metrics は (名前, 単位, 値) の組
+fn emit_metrics(job: &str, metrics: &[(&str, &str, u64)]) {
+    let mut body = Map::new();
+    body.insert(
+        "_aws".into(),
+        json!({
+            "Timestamp": now().timestamp_millis(),
+            "CloudWatchMetrics": [{
+                "Namespace": "Batch",
+                "Dimensions": [["Job"]],
+                "Metrics": metrics
+                    .iter()
+                    .map(|(name, unit, _)| json!({"Name": name, "Unit": unit}))
+                    .collect::<Vec<_>>(),
+            }],
+        }),
+    );
+    body.insert("Job".into(), job.into());
+    for (name, _, value) in metrics {
+        body.insert(name.to_string(), (*value).into());
+    }
+    println!("{}", Value::Object(body));
+}
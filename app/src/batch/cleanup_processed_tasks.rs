@@ -0,0 +1,18 @@
+use crate::domain::types::time::now;
+use crate::{App, AppResult};
+use chrono::Duration;
+
+// SQSのメッセージ保持期間の上限。これより古いメッセージが再配信されることはない
+const RETENTION_DAYS: i64 = 14;
+
+// 重複配信の判定に使う処理済みタスクの記録を削除する
+pub async fn exec(app: &App) -> AppResult<()> {
+    let threshold = now() - Duration::days(RETENTION_DAYS);
+    let deleted = app
+        .processed_task_repository
+        .delete_before(app.db_session.conn(), threshold)
+        .await?;
+
+    tracing::info!("cleanup processed tasks: deleted={}", deleted);
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use crate::domain::types::time::now;
+use crate::{App, AppResult};
+use chrono::Duration;
+
+const TMP_PREFIX: &str = "tmp/";
+
+// 確定されずに放置されたアップロードを削除する
+pub async fn exec(app: &App) -> AppResult<()> {
+    let threshold = now() - Duration::hours(app.env.tmp_upload_retention_hours);
+
+    let mut scanned = 0;
+    let mut deleted_objects = 0;
+    let mut deleted_bytes = 0;
+    let mut continuation_token: Option<String> = None;
+    loop {
+        let page = app
+            .storage
+            .list_objects(TMP_PREFIX, continuation_token.take())
+            .await?;
+        scanned += page.objects.len() as u64;
+
+        let expired = page
+            .objects
+            .into_iter()
+            .filter(|v| v.last_modified.map(|t| t < threshold).unwrap_or(false))
+            .collect::<Vec<_>>();
+        if !expired.is_empty() {
+            let keys = expired.iter().map(|v| v.key.clone()).collect::<Vec<_>>();
+            deleted_objects += app.storage.delete_objects(&keys).await?;
+            deleted_bytes += expired.iter().map(|v| v.size).sum::<u64>();
+        }
+
+        match page.next_token {
+            Some(v) => continuation_token = Some(v),
+            None => break,
+        }
+    }
+
+    // asset/ 直下に直接アップロードされたものは tmp/ の掃除で消えないので、行ごとに消してから削除する
+    let mut deleted_assets = 0;
+    let expired = app
+        .asset_repository
+        .find_pending_before(app.db_session.conn(), threshold)
+        .await?;
+    for asset in expired {
+        let mut keys = vec![asset.key.clone()];
+        if asset.needs_promotion() {
+            keys.push(asset.upload_key.clone());
+        }
+        deleted_objects += app.storage.delete_objects(&keys).await?;
+        app.asset_repository
+            .delete(app.db_session.conn(), &asset.id)
+            .await?;
+        deleted_assets += 1;
+    }
+
+    tracing::info!(
+        "cleanup tmp uploads: scanned={}, deleted_objects={}, deleted_bytes={}, deleted_assets={}",
+        scanned,
+        deleted_objects,
+        deleted_bytes,
+        deleted_assets
+    );
+    super::emit_metrics(
+        "CleanupTmpUploads",
+        &[
+            ("DeletedObjects", "Count", deleted_objects),
+            ("DeletedBytes", "Bytes", deleted_bytes),
+            ("DeletedAssets", "Count", deleted_assets),
+        ],
+    );
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::domain::scheduled_task::ScheduledTask;
+use crate::domain::types::time::now;
+use crate::{App, AppResult, worker};
+use chrono::Duration;
+
+const BATCH_SIZE: u64 = 100;
+// 投入してからこれだけ経っても Enqueued のままのタスクは、投入が失敗したものとみなして投入し直す
+// 重複して投入されても冪等キーで一度しか実行されない
+const STALE_AFTER_HOURS: i64 = 1;
+// 処理が失敗し続けるタスクやDLQに移ったタスクを投入し続けないよう、投入回数に上限を設ける
+const MAX_ATTEMPTS: u32 = 3;
+
+// 実行時刻が投入できる範囲に入ったタスクを投入する
+pub async fn exec(app: &App) -> AppResult<()> {
+    let until = now() + worker::dispatch_window(app);
+
+    let mut dispatched = 0;
+    let mut failed = 0;
+    loop {
+        let tasks = app
+            .scheduled_task_repository
+            .find_due(app.db_session.conn(), until, BATCH_SIZE)
+            .await?;
+        let fetched = tasks.len() as u64;
+        let (ok, ng) = dispatch(app, tasks).await;
+        dispatched += ok;
+        failed += ng;
+        // 失敗したタスクは Pending のまま残るため、進まなくなったら次回に回す
+        if fetched < BATCH_SIZE || ok == 0 {
+            break;
+        }
+    }
+
+    let stale = app
+        .scheduled_task_repository
+        .find_stale(
+            app.db_session.conn(),
+            now() - Duration::hours(STALE_AFTER_HOURS),
+            BATCH_SIZE,
+        )
+        .await?;
+    let (retry, give_up): (Vec<_>, Vec<_>) =
+        stale.into_iter().partition(|v| v.attempts < MAX_ATTEMPTS);
+    let reclaimed = retry.len() as u64;
+    for task in &retry {
+        tracing::warn!(
+            "reclaim stale scheduled task {} (attempts={})",
+            task.id,
+            task.attempts
+        );
+    }
+    let (ok, ng) = dispatch(app, retry).await;
+    dispatched += ok;
+    failed += ng;
+
+    let abandoned = give_up.len() as u64;
+    for task in give_up {
+        tracing::error!(
+            "give up scheduled task {} after {} attempts",
+            task.id,
+            task.attempts
+        );
+        app.scheduled_task_repository
+            .update(app.db_session.conn(), task.fail())
+            .await?;
+    }
+
+    tracing::info!(
+        "dispatch scheduled tasks: dispatched={}, failed={}, reclaimed={}, abandoned={}",
+        dispatched,
+        failed,
+        reclaimed,
+        abandoned
+    );
+    super::emit_metrics(
+        "DispatchScheduledTasks",
+        &[
+            ("DispatchedTasks", "Count", dispatched),
+            ("FailedTasks", "Count", failed),
+            ("ReclaimedTasks", "Count", reclaimed),
+            ("AbandonedTasks", "Count", abandoned),
+        ],
+    );
+    Ok(())
+}
+
+async fn dispatch(app: &App, tasks: Vec<ScheduledTask>) -> (u64, u64) {
+    let mut dispatched = 0;
+    let mut failed = 0;
+    for task in tasks {
+        let id = task.id.clone();
+        match worker::dispatch_scheduled(app, task).await {
+            Ok(_) => dispatched += 1,
+            Err(err) => {
+                tracing::error!("failed to dispatch scheduled task {}: {:?}", id, err);
+                failed += 1;
+            }
+        }
+    }
+    (dispatched, failed)
+}
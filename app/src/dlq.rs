@@ -0,0 +1,142 @@
+use crate::adapter::ReceivedMessage;
+use crate::domain::types::task::AsyncTaskMessage;
+use crate::errors::Kind::BadRequest;
+use crate::{App, AppResult, worker};
+use std::collections::HashSet;
+use std::time::Duration;
+
+// 操作中に他の受信者へ渡らないよう隠しておく時間
+const VISIBILITY_TIMEOUT: Duration = Duration::from_secs(30);
+// SQSの1回の受信数の上限
+const RECEIVE_BATCH_SIZE: usize = 10;
+pub const MAX_MESSAGES: usize = 100;
+
+// DLQに溜まったメッセージ。本文をタスクとして読めない場合は task が None
+#[derive(Debug, Clone)]
+pub struct DeadLetter {
+    pub message: ReceivedMessage,
+    pub task: Option<AsyncTaskMessage>,
+}
+impl From<ReceivedMessage> for DeadLetter {
+    fn from(message: ReceivedMessage) -> Self {
+        let task = serde_json::from_str(&message.body).ok();
+        Self { message, task }
+    }
+}
+
+// SQSには一覧取得がないため、受信した後すぐに可視性を戻す
+pub async fn list(app: &App, limit: usize) -> AppResult<Vec<DeadLetter>> {
+    let url = dlq_url(app)?;
+    let messages = receive(app, url, limit.min(MAX_MESSAGES)).await?;
+    release(app, url, &messages).await;
+    Ok(messages.into_iter().map(DeadLetter::from).collect())
+}
+
+// 指定したメッセージを元のキューへ戻し、戻せたメッセージのIDを返す
+pub async fn redrive(app: &App, message_ids: &[String]) -> AppResult<Vec<String>> {
+    let url = dlq_url(app)?;
+    let (targets, others) = receive_targets(app, url, message_ids).await?;
+    release(app, url, &others).await;
+
+    let mut redriven = vec![];
+    for message in targets {
+        match redrive_message(app, url, &message).await {
+            Ok(_) => redriven.push(message.message_id),
+            Err(err) => {
+                tracing::error!(
+                    "failed to redrive message {}: {:?}",
+                    message.message_id,
+                    err
+                );
+                release(app, url, &[message]).await;
+            }
+        }
+    }
+    Ok(redriven)
+}
+
+// 何度実行しても失敗するメッセージを破棄し、削除できたメッセージのIDを返す
+pub async fn delete(app: &App, message_ids: &[String]) -> AppResult<Vec<String>> {
+    let url = dlq_url(app)?;
+    let (targets, others) = receive_targets(app, url, message_ids).await?;
+    release(app, url, &others).await;
+
+    let mut deleted = vec![];
+    for message in targets {
+        match app
+            .sqs_queue_reader
+            .delete(url, &message.receipt_handle)
+            .await
+        {
+            Ok(_) => deleted.push(message.message_id),
+            Err(err) => {
+                tracing::error!("failed to delete message {}: {:?}", message.message_id, err);
+                release(app, url, &[message]).await;
+            }
+        }
+    }
+    Ok(deleted)
+}
+
+fn dlq_url(app: &App) -> AppResult<&str> {
+    app.env
+        .sqs_async_task_dlq_url
+        .as_deref()
+        .ok_or_else(|| BadRequest.with("SQS_ASYNC_TASK_DLQ_URL is not set"))
+}
+
+async fn redrive_message(app: &App, url: &str, message: &ReceivedMessage) -> AppResult<()> {
+    let task: AsyncTaskMessage =
+        serde_json::from_str(&message.body).map_err(BadRequest.from_srcf())?;
+    // 冪等キーは引き継ぐ。失敗時に処理中の記録は消されている(消せなくても一定時間後に取り直せる)ため再実行される
+    worker::publish(app, task).await?;
+    app.sqs_queue_reader
+        .delete(url, &message.receipt_handle)
+        .await
+}
+
+async fn receive(app: &App, url: &str, limit: usize) -> AppResult<Vec<ReceivedMessage>> {
+    let mut messages = vec![];
+    while messages.len() < limit {
+        let max = RECEIVE_BATCH_SIZE.min(limit - messages.len());
+        let received = app
+            .sqs_queue_reader
+            .receive(url, max as i32, VISIBILITY_TIMEOUT)
+            .await?;
+        // ロングポーリングなので、空で返れば残りのメッセージはない
+        if received.is_empty() {
+            break;
+        }
+        messages.extend(received);
+    }
+    Ok(messages)
+}
+
+// 指定されたメッセージとそれ以外に分ける
+async fn receive_targets(
+    app: &App,
+    url: &str,
+    message_ids: &[String],
+) -> AppResult<(Vec<ReceivedMessage>, Vec<ReceivedMessage>)> {
+    let ids: HashSet<&str> = message_ids.iter().map(|v| v.as_str()).collect();
+    let messages = receive(app, url, MAX_MESSAGES).await?;
+    Ok(messages
+        .into_iter()
+        .partition(|v| ids.contains(v.message_id.as_str())))
+}
+
+async fn release(app: &App, url: &str, messages: &[ReceivedMessage]) {
+    for message in messages {
+        if let Err(err) = app
+            .sqs_queue_reader
+            .release(url, &message.receipt_handle)
+            .await
+        {
+            tracing::warn!(
+                "failed to release message {}: {:?}",
+                message.message_id,
+                err
+            );
+        }
+    }
+}
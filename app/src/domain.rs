@@ -1,5 +1,12 @@
 pub mod admin_user;
+pub mod api_key;
+pub mod asset;
+pub mod device_token;
+pub mod email_suppression;
+pub mod notification;
 pub mod order;
+pub mod processed_task;
+pub mod scheduled_task;
 pub mod types;
 pub mod user;
 
@@ -89,7 +96,7 @@ impl<'de, E> Deserialize<'de> for Id<E> {
     }
 }
 
-fn generate_id_str() -> String {
+pub(crate) fn generate_id_str() -> String {
     base_62::encode(&random::<[u8; 16]>())
 }
 
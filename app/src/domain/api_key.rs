@@ -0,0 +1,177 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::HasId;
+use crate::domain::types::string::impl_len_restricted_string_model;
+use crate::domain::types::time::{LocalDateTime, now};
+use async_trait::async_trait;
+use rand::random;
+use sha2::{Digest, Sha256};
+
+const SECRET_PREFIX: &str = "ak_";
+const DISPLAY_PREFIX_LEN: usize = 11;
+
+pub type Id = crate::domain::Id<ApiKey>;
+#[derive(Debug, Clone)]
+pub struct ApiKey {
+    pub id: Id,
+    pub name: Name,
+    pub key_prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<Scope>,
+    pub expires_at: Option<LocalDateTime>,
+    pub revoked_at: Option<LocalDateTime>,
+    pub last_used_at: Option<LocalDateTime>,
+    pub created_at: LocalDateTime,
+    pub updated_at: LocalDateTime,
+}
+impl ApiKey {
+    pub fn issue(
+        name: Name,
+        scopes: Vec<Scope>,
+        expires_at: Option<LocalDateTime>,
+    ) -> (Self, Secret) {
+        let secret = Secret::generate();
+        let key = Self {
+            id: Id::generate(),
+            name,
+            key_prefix: secret.display_prefix(),
+            key_hash: secret.hash(),
+            scopes,
+            expires_at,
+            revoked_at: None,
+            last_used_at: None,
+            created_at: now(),
+            updated_at: now(),
+        };
+        (key, secret)
+    }
+
+    pub fn rotate(self) -> (Self, Secret) {
+        let secret = Secret::generate();
+        let key = Self {
+            key_prefix: secret.display_prefix(),
+            key_hash: secret.hash(),
+            last_used_at: None,
+            updated_at: now(),
+            ..self
+        };
+        (key, secret)
+    }
+
+    pub fn revoke(self) -> Self {
+        Self {
+            revoked_at: Some(now()),
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    pub fn touch(self) -> Self {
+        Self {
+            last_used_at: Some(now()),
+            ..self
+        }
+    }
+
+    pub fn should_touch(&self) -> bool {
+        match self.last_used_at {
+            None => true,
+            Some(v) => now().signed_duration_since(v).num_minutes() >= 1,
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        if self.revoked_at.is_some() {
+            return false;
+        }
+        match self.expires_at {
+            None => true,
+            Some(v) => v > now(),
+        }
+    }
+
+    pub fn has_scope(&self, scope: Scope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+impl HasId for ApiKey {
+    type Entity = Self;
+    fn id(&self) -> &crate::domain::Id<Self> {
+        &self.id
+    }
+}
+
+impl_len_restricted_string_model!(Name, "APIキー名", 1, 255);
+
+// 発行時・ローテーション時に一度だけ返す平文のキー
+#[derive(Debug, Clone)]
+pub struct Secret(String);
+impl Secret {
+    fn generate() -> Self {
+        Self(format!(
+            "{}{}",
+            SECRET_PREFIX,
+            base_62::encode(&random::<[u8; 32]>())
+        ))
+    }
+
+    pub fn hash(&self) -> String {
+        hash_secret(&self.0)
+    }
+
+    pub fn display_prefix(&self) -> String {
+        self.0.chars().take(DISPLAY_PREFIX_LEN).collect()
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+}
+
+pub fn hash_secret(secret: &str) -> String {
+    hex::encode(Sha256::digest(secret.as_bytes()))
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    Hash,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    async_graphql::Enum,
+)]
+pub enum Scope {
+    #[strum(serialize = "user:read")]
+    UserRead,
+    #[strum(serialize = "order:read")]
+    OrderRead,
+}
+impl Scope {
+    pub fn parse_list(value: &str) -> Result<Vec<Self>, String> {
+        value
+            .split(',')
+            .filter(|v| !v.is_empty())
+            .map(|v| v.parse().map_err(|e| format!("error: {:?}", e)))
+            .collect()
+    }
+
+    pub fn join(scopes: &[Self]) -> String {
+        scopes
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync {
+    async fn find(&self, db: DbConn<'_>) -> AppResult<Vec<ApiKey>>;
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<ApiKey>;
+    async fn get_by_hash(&self, db: DbConn<'_>, key_hash: &str) -> AppResult<ApiKey>;
+    async fn insert(&self, db: DbConn<'_>, api_key: ApiKey) -> AppResult<()>;
+    async fn update(&self, db: DbConn<'_>, api_key: ApiKey) -> AppResult<()>;
+}
@@ -0,0 +1,154 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::types::asset_key::AssetKey;
+use crate::domain::types::time::{LocalDateTime, now};
+use crate::domain::{HasId, user};
+use async_trait::async_trait;
+
+pub type Id = crate::domain::Id<Asset>;
+#[derive(Debug, Clone)]
+pub struct Asset {
+    pub id: Id,
+    pub user_id: user::Id,
+    pub key: AssetKey,
+    pub upload_key: AssetKey,
+    pub status: Status,
+    pub content_type: Option<String>,
+    pub size: Option<u64>,
+    pub checksum: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub created_at: LocalDateTime,
+    pub updated_at: LocalDateTime,
+}
+impl Asset {
+    // upload_keyがtmp配下の場合は確定時にkeyへ移動する
+    pub fn new(
+        id: Id,
+        user_id: user::Id,
+        key: AssetKey,
+        upload_key: AssetKey,
+        content_type: Option<String>,
+    ) -> Self {
+        Self {
+            id,
+            user_id,
+            key,
+            upload_key,
+            status: Status::Pending,
+            content_type,
+            size: None,
+            checksum: None,
+            width: None,
+            height: None,
+            created_at: now(),
+            updated_at: now(),
+        }
+    }
+
+    pub fn confirm(
+        self,
+        content_type: Option<String>,
+        size: u64,
+        checksum: Option<String>,
+    ) -> Self {
+        Self {
+            status: Status::Confirmed,
+            content_type: content_type.or(self.content_type),
+            size: Some(size),
+            checksum,
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    // メタデータを落として上書きした後のサイズとチェックサムに更新する
+    pub fn processed(self, width: u32, height: u32, size: u64, checksum: Option<String>) -> Self {
+        Self {
+            size: Some(size),
+            checksum,
+            width: Some(width),
+            height: Some(height),
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    pub fn reject(self) -> Self {
+        Self {
+            status: Status::Rejected,
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    pub fn is_image(&self) -> bool {
+        self.content_type
+            .as_deref()
+            .map(|v| v.starts_with("image/"))
+            .unwrap_or(false)
+    }
+
+    // 画像処理が終わるまでは未処理扱い
+    pub fn needs_processing(&self) -> bool {
+        self.status == Status::Confirmed && self.is_image() && self.width.is_none()
+    }
+
+    pub fn needs_promotion(&self) -> bool {
+        self.key != self.upload_key
+    }
+
+    pub fn is_owned_by(&self, user_id: &user::Id) -> bool {
+        &self.user_id == user_id
+    }
+}
+impl HasId for Asset {
+    type Entity = Self;
+    fn id(&self) -> &crate::domain::Id<Self> {
+        &self.id
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    async_graphql::Enum,
+)]
+pub enum Status {
+    Pending,
+    Confirmed,
+    // 画像として不正なため破棄された
+    Rejected,
+}
+impl TryFrom<String> for Status {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse().map_err(|e| format!("error: {:?}", e))
+    }
+}
+impl Into<String> for Status {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+#[async_trait]
+pub trait AssetRepository: Send + Sync {
+    async fn find_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<Vec<Asset>>;
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<Asset>;
+    async fn get_multi(&self, db: DbConn<'_>, ids: Vec<&Id>) -> AppResult<Vec<Asset>>;
+    async fn insert(&self, db: DbConn<'_>, asset: Asset) -> AppResult<()>;
+    async fn update(&self, db: DbConn<'_>, asset: Asset) -> AppResult<()>;
+    async fn delete(&self, db: DbConn<'_>, id: &Id) -> AppResult<()>;
+    async fn delete_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<()>;
+    async fn find_pending_before(
+        &self,
+        db: DbConn<'_>,
+        before: LocalDateTime,
+    ) -> AppResult<Vec<Asset>>;
+}
@@ -0,0 +1,78 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::types::time::{LocalDateTime, now};
+use crate::domain::{HasId, user};
+use async_trait::async_trait;
+
+// プッシュ通知の送信先となる端末のFCMトークン
+pub type Id = crate::domain::Id<DeviceToken>;
+#[derive(Debug, Clone)]
+pub struct DeviceToken {
+    pub id: Id,
+    pub user_id: user::Id,
+    pub token: String,
+    pub platform: Platform,
+    pub created_at: LocalDateTime,
+    pub updated_at: LocalDateTime,
+}
+impl DeviceToken {
+    pub fn new(user_id: user::Id, token: String, platform: Platform) -> Self {
+        Self {
+            id: Id::generate(),
+            user_id,
+            token,
+            platform,
+            created_at: now(),
+            updated_at: now(),
+        }
+    }
+}
+impl HasId for DeviceToken {
+    type Entity = Self;
+    fn id(&self) -> &crate::domain::Id<Self> {
+        &self.id
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    async_graphql::Enum,
+)]
+pub enum Platform {
+    Ios,
+    Android,
+    Web,
+}
+impl TryFrom<String> for Platform {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse().map_err(|e| format!("error: {:?}", e))
+    }
+}
+impl Into<String> for Platform {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+#[async_trait]
+pub trait DeviceTokenRepository: Send + Sync {
+    async fn find_by_user(&self, db: DbConn<'_>, user_id: &user::Id)
+    -> AppResult<Vec<DeviceToken>>;
+    // 同じトークンが別ユーザーで登録されている場合は付け替える
+    async fn upsert(&self, db: DbConn<'_>, device_token: DeviceToken) -> AppResult<()>;
+    async fn delete_by_token(
+        &self,
+        db: DbConn<'_>,
+        user_id: &user::Id,
+        token: &str,
+    ) -> AppResult<u64>;
+    async fn delete_by_tokens(&self, db: DbConn<'_>, tokens: Vec<String>) -> AppResult<u64>;
+    async fn delete_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<()>;
+}
@@ -0,0 +1,77 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::HasId;
+use crate::domain::types::email::Email;
+use crate::domain::types::time::{LocalDateTime, now};
+use async_trait::async_trait;
+
+// バウンス・苦情が報告されたアドレス。登録されている間はメールを送信しない
+pub type Id = crate::domain::Id<EmailSuppression>;
+#[derive(Debug, Clone)]
+pub struct EmailSuppression {
+    pub id: Id,
+    pub email: Email,
+    pub reason: Reason,
+    pub detail: Option<String>,
+    pub created_at: LocalDateTime,
+    pub updated_at: LocalDateTime,
+}
+impl EmailSuppression {
+    // 照合時と揃えるため小文字で保存する
+    pub fn new(email: Email, reason: Reason, detail: Option<String>) -> Self {
+        Self {
+            id: Id::generate(),
+            email: email.to_lowercase(),
+            reason,
+            detail,
+            created_at: now(),
+            updated_at: now(),
+        }
+    }
+}
+impl HasId for EmailSuppression {
+    type Entity = Self;
+    fn id(&self) -> &crate::domain::Id<Self> {
+        &self.id
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    async_graphql::Enum,
+)]
+pub enum Reason {
+    Bounce,
+    Complaint,
+}
+impl TryFrom<String> for Reason {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse().map_err(|e| format!("error: {:?}", e))
+    }
+}
+impl Into<String> for Reason {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+#[async_trait]
+pub trait EmailSuppressionRepository: Send + Sync {
+    async fn find(&self, db: DbConn<'_>) -> AppResult<Vec<EmailSuppression>>;
+    async fn find_by_emails(
+        &self,
+        db: DbConn<'_>,
+        emails: Vec<&Email>,
+    ) -> AppResult<Vec<EmailSuppression>>;
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<EmailSuppression>;
+    // 同じアドレスが既にある場合は理由を更新する
+    async fn upsert(&self, db: DbConn<'_>, suppression: EmailSuppression) -> AppResult<()>;
+    async fn delete(&self, db: DbConn<'_>, id: &Id) -> AppResult<()>;
+}
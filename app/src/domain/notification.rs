@@ -0,0 +1,145 @@
+pub mod preference;
+
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::types::time::{LocalDateTime, now};
+use crate::domain::{HasId, user};
+use async_trait::async_trait;
+use serde_json::Value;
+
+// アプリ内のお知らせ。payload の中身は notification_type ごとにクライアントが解釈する
+pub type Id = crate::domain::Id<Notification>;
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub id: Id,
+    pub user_id: user::Id,
+    pub notification_type: Type,
+    pub payload: Value,
+    pub read_at: Option<LocalDateTime>,
+    pub created_at: LocalDateTime,
+    pub updated_at: LocalDateTime,
+}
+impl Notification {
+    pub fn new(user_id: user::Id, notification_type: Type, payload: Value) -> Self {
+        Self {
+            id: Id::generate(),
+            user_id,
+            notification_type,
+            payload,
+            read_at: None,
+            created_at: now(),
+            updated_at: now(),
+        }
+    }
+
+    pub fn mark_read(self) -> Self {
+        if self.is_read() {
+            return self;
+        }
+        Self {
+            read_at: Some(now()),
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    pub fn is_read(&self) -> bool {
+        self.read_at.is_some()
+    }
+
+    pub fn is_owned_by(&self, user_id: &user::Id) -> bool {
+        &self.user_id == user_id
+    }
+}
+impl HasId for Notification {
+    type Entity = Self;
+    fn id(&self) -> &crate::domain::Id<Self> {
+        &self.id
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    async_graphql::Enum,
+)]
+pub enum Type {
+    DataExportReady,
+}
+impl Type {
+    pub fn category(&self) -> Category {
+        match self {
+            Type::DataExportReady => Category::Account,
+        }
+    }
+}
+impl TryFrom<String> for Type {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse().map_err(|e| format!("error: {:?}", e))
+    }
+}
+impl Into<String> for Type {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+// 受信設定の単位
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    async_graphql::Enum,
+)]
+pub enum Category {
+    // 手続きの完了などアカウントに関するお知らせ
+    Account,
+    // キャンペーンなどのお知らせ
+    Marketing,
+}
+impl Category {
+    pub fn parse_list(value: &str) -> Result<Vec<Self>, String> {
+        value
+            .split(',')
+            .filter(|v| !v.is_empty())
+            .map(|v| v.parse().map_err(|e| format!("error: {:?}", e)))
+            .collect()
+    }
+
+    pub fn join(categories: &[Self]) -> String {
+        categories
+            .iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+#[async_trait]
+pub trait NotificationRepository: Send + Sync {
+    // 新しい順に返す
+    async fn find_by_user(
+        &self,
+        db: DbConn<'_>,
+        user_id: &user::Id,
+        offset: u64,
+        limit: u64,
+    ) -> AppResult<Vec<Notification>>;
+    async fn count_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<u64>;
+    async fn count_unread_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<u64>;
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<Notification>;
+    async fn insert(&self, db: DbConn<'_>, notification: Notification) -> AppResult<()>;
+    async fn update(&self, db: DbConn<'_>, notification: Notification) -> AppResult<()>;
+    async fn mark_all_read(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<u64>;
+    async fn delete_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<()>;
+}
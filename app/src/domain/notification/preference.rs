@@ -0,0 +1,117 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::notification::Category;
+use crate::domain::types::time::{LocalDateTime, LocalDateTimeExt, now};
+use crate::domain::{HasId, user};
+use async_trait::async_trait;
+use chrono::Timelike;
+
+// 通知の受信設定。未設定のユーザーは全て受信する
+pub type Id = crate::domain::Id<Preference>;
+#[derive(Debug, Clone)]
+pub struct Preference {
+    pub id: Id,
+    pub user_id: user::Id,
+    pub push_opt_outs: Vec<Category>,
+    pub email_opt_outs: Vec<Category>,
+    pub quiet_hours: Option<QuietHours>,
+    pub created_at: LocalDateTime,
+    pub updated_at: LocalDateTime,
+}
+impl Preference {
+    pub fn new(user_id: user::Id) -> Self {
+        Self {
+            id: Id::generate(),
+            user_id,
+            push_opt_outs: vec![],
+            email_opt_outs: vec![],
+            quiet_hours: None,
+            created_at: now(),
+            updated_at: now(),
+        }
+    }
+
+    pub fn update(
+        self,
+        push_opt_outs: Vec<Category>,
+        email_opt_outs: Vec<Category>,
+        quiet_hours: Option<QuietHours>,
+    ) -> Self {
+        Self {
+            push_opt_outs,
+            email_opt_outs,
+            quiet_hours,
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    // おやすみ時間はプッシュ通知のみ止める
+    pub fn allows(&self, channel: Channel, category: Category, at: LocalDateTime) -> bool {
+        match channel {
+            Channel::Push => {
+                !self.push_opt_outs.contains(&category)
+                    && !self.quiet_hours.is_some_and(|v| v.contains(at))
+            }
+            Channel::Email => !self.email_opt_outs.contains(&category),
+        }
+    }
+}
+impl HasId for Preference {
+    type Entity = Self;
+    fn id(&self) -> &crate::domain::Id<Self> {
+        &self.id
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    async_graphql::Enum,
+)]
+pub enum Channel {
+    Push,
+    Email,
+}
+
+// JSTの時(0-23)で指定する。start > end の場合は日付をまたぐ
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct QuietHours {
+    pub start_hour: u32,
+    pub end_hour: u32,
+}
+impl QuietHours {
+    pub fn new(start_hour: u32, end_hour: u32) -> Result<Self, String> {
+        if start_hour > 23 || end_hour > 23 {
+            return Err("時刻は0から23で指定してください".into());
+        }
+        if start_hour == end_hour {
+            return Err("開始と終了に同じ時刻は指定できません".into());
+        }
+        Ok(Self {
+            start_hour,
+            end_hour,
+        })
+    }
+
+    pub fn contains(&self, at: LocalDateTime) -> bool {
+        let hour = at.to_jst().hour();
+        if self.start_hour < self.end_hour {
+            self.start_hour <= hour && hour < self.end_hour
+        } else {
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+#[async_trait]
+pub trait NotificationPreferenceRepository: Send + Sync {
+    async fn get_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<Preference>;
+    async fn upsert(&self, db: DbConn<'_>, preference: Preference) -> AppResult<()>;
+    async fn delete_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<()>;
+}
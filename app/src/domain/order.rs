@@ -11,7 +11,8 @@ pub type Id = crate::domain::Id<Order>;
 #[derive(Debug, Clone)]
 pub struct Order {
     pub id: Id,
-    pub user_id: user::Id,
+    // 退会時に匿名化された注文はNone
+    pub user_id: Option<user::Id>,
     pub created_at: LocalDateTime,
     pub updated_at: LocalDateTime,
 }
@@ -19,7 +20,7 @@ impl Order {
     pub fn new(user: &User) -> Self {
         Self {
             id: Id::generate(),
-            user_id: user.id.clone(),
+            user_id: Some(user.id.clone()),
             created_at: now(),
             updated_at: now(),
         }
@@ -41,4 +42,5 @@ pub trait OrderRepository: Send + Sync {
     async fn insert(&self, db: DbConn<'_>, order: Order) -> AppResult<()>;
     async fn update(&self, db: DbConn<'_>, order: Order) -> AppResult<()>;
     async fn delete(&self, db: DbConn<'_>, id: &Id) -> AppResult<()>;
+    async fn anonymize_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<()>;
 }
@@ -51,4 +51,5 @@ pub trait OrderDetailRepository: Send + Sync {
     async fn insert(&self, db: DbConn<'_>, detail: Detail) -> AppResult<()>;
     async fn update(&self, db: DbConn<'_>, detail: Detail) -> AppResult<()>;
     async fn delete(&self, db: DbConn<'_>, id: &Id) -> AppResult<()>;
+    async fn delete_by_order(&self, db: DbConn<'_>, order_id: &order::Id) -> AppResult<()>;
 }
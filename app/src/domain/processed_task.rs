@@ -0,0 +1,85 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::HasId;
+use crate::domain::types::time::{LocalDateTime, now};
+use async_trait::async_trait;
+
+// 受信したタスクの処理状況。id に受信側とメッセージの冪等キーを使い、重複配信を検知する
+pub type Id = crate::domain::Id<ProcessedTask>;
+#[derive(Debug, Clone)]
+pub struct ProcessedTask {
+    pub id: Id,
+    pub task_type: String,
+    pub status: Status,
+    pub created_at: LocalDateTime,
+    pub updated_at: LocalDateTime,
+}
+impl ProcessedTask {
+    // SNSは購読先ごとに同じメッセージを配信するため、受信側ごとに記録を分ける
+    pub fn new(consumer: Consumer, key: &str, task_type: impl Into<String>) -> Self {
+        Self {
+            id: format!("{}:{}", consumer, key).into(),
+            task_type: task_type.into(),
+            status: Status::Processing,
+            created_at: now(),
+            updated_at: now(),
+        }
+    }
+
+    pub fn complete(self) -> Self {
+        Self {
+            status: Status::Completed,
+            updated_at: now(),
+            ..self
+        }
+    }
+}
+impl HasId for ProcessedTask {
+    type Entity = Self;
+    fn id(&self) -> &crate::domain::Id<Self> {
+        &self.id
+    }
+}
+
+// タスクを受け取る関数
+#[derive(Debug, Clone, Copy, Eq, PartialEq, strum_macros::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum Consumer {
+    AsyncSns,
+    AsyncSqs,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, strum_macros::EnumString, strum_macros::Display)]
+pub enum Status {
+    // 処理を始める前に記録し、同じメッセージを並行して処理しないようにする
+    Processing,
+    Completed,
+}
+impl TryFrom<String> for Status {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse().map_err(|e| format!("error: {:?}", e))
+    }
+}
+impl Into<String> for Status {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+#[async_trait]
+pub trait ProcessedTaskRepository: Send + Sync {
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<ProcessedTask>;
+    // 記録済みであれば Duplicate を返す
+    async fn insert(&self, db: DbConn<'_>, task: ProcessedTask) -> AppResult<()>;
+    async fn update(&self, db: DbConn<'_>, task: ProcessedTask) -> AppResult<()>;
+    // stale_before より前から Processing のままのものを取り直す。取り直せた場合は true を返す
+    async fn reclaim(
+        &self,
+        db: DbConn<'_>,
+        id: &Id,
+        stale_before: LocalDateTime,
+    ) -> AppResult<bool>;
+    async fn delete(&self, db: DbConn<'_>, id: &Id) -> AppResult<()>;
+    async fn delete_before(&self, db: DbConn<'_>, until: LocalDateTime) -> AppResult<u64>;
+}
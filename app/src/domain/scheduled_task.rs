@@ -0,0 +1,134 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::HasId;
+use crate::domain::types::task::AsyncTaskPayload;
+use crate::domain::types::time::{LocalDateTime, now};
+use async_trait::async_trait;
+
+// 指定時刻に実行する非同期タスク。SQSの遅延上限を超えるものはbatch_fnが実行時刻の直前に投入する
+pub type Id = crate::domain::Id<ScheduledTask>;
+#[derive(Debug, Clone)]
+pub struct ScheduledTask {
+    pub id: Id,
+    pub payload: AsyncTaskPayload,
+    pub run_at: LocalDateTime,
+    pub status: Status,
+    // SQSへ投入した回数
+    pub attempts: u32,
+    pub created_at: LocalDateTime,
+    pub updated_at: LocalDateTime,
+}
+impl ScheduledTask {
+    pub fn new(payload: AsyncTaskPayload, run_at: LocalDateTime) -> Self {
+        Self {
+            id: Id::generate(),
+            payload,
+            run_at,
+            status: Status::Pending,
+            attempts: 0,
+            created_at: now(),
+            updated_at: now(),
+        }
+    }
+
+    // updated_at が投入した時刻になる
+    pub fn enqueue(self) -> Self {
+        Self {
+            status: Status::Enqueued,
+            attempts: self.attempts + 1,
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    // 投入し直しても実行されなかったものは諦める
+    pub fn fail(self) -> Self {
+        Self {
+            status: Status::Failed,
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    pub fn complete(self) -> Self {
+        Self {
+            status: Status::Done,
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    pub fn cancel(self) -> Result<Self, String> {
+        if !self.is_cancellable() {
+            return Err(format!("{}のタスクはキャンセルできません", self.status));
+        }
+        Ok(Self {
+            status: Status::Cancelled,
+            updated_at: now(),
+            ..self
+        })
+    }
+
+    pub fn is_cancellable(&self) -> bool {
+        matches!(self.status, Status::Pending | Status::Enqueued)
+    }
+}
+impl HasId for ScheduledTask {
+    type Entity = Self;
+    fn id(&self) -> &crate::domain::Id<Self> {
+        &self.id
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    async_graphql::Enum,
+)]
+pub enum Status {
+    // 実行時刻までDBで待機している
+    Pending,
+    // SQSに投入済み
+    Enqueued,
+    Done,
+    Cancelled,
+    // 投入し直す回数の上限に達した
+    Failed,
+}
+impl TryFrom<String> for Status {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse().map_err(|e| format!("error: {:?}", e))
+    }
+}
+impl Into<String> for Status {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+#[async_trait]
+pub trait ScheduledTaskRepository: Send + Sync {
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<ScheduledTask>;
+    // run_at が until 以前の Pending のタスクを実行時刻順に返す
+    async fn find_due(
+        &self,
+        db: DbConn<'_>,
+        until: LocalDateTime,
+        limit: u64,
+    ) -> AppResult<Vec<ScheduledTask>>;
+    // before 以前に投入されたまま Enqueued で残っているタスクを投入した順に返す
+    async fn find_stale(
+        &self,
+        db: DbConn<'_>,
+        before: LocalDateTime,
+        limit: u64,
+    ) -> AppResult<Vec<ScheduledTask>>;
+    async fn insert(&self, db: DbConn<'_>, task: ScheduledTask) -> AppResult<()>;
+    async fn update(&self, db: DbConn<'_>, task: ScheduledTask) -> AppResult<()>;
+}
@@ -1,7 +1,11 @@
 pub mod asset_key;
 pub mod email;
 pub mod image_size;
+pub mod image_transform;
+pub mod outgoing_mail;
 pub mod pager;
+pub mod ses_notification;
 pub mod string;
 pub mod task;
 pub mod time;
+pub mod upload_policy;
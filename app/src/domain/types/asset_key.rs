@@ -1,3 +1,4 @@
+use crate::domain::types::image_size::ImageSize;
 use crate::domain::types::string::impl_len_restricted_string_model;
 use crate::domain::user;
 
@@ -9,4 +10,30 @@ impl AssetKey {
     pub fn temp_key(user_id: user::Id, file_name: String) -> Self {
         Self(format!("tmp/{}/{}", user_id.as_str(), file_name))
     }
+    // 画像処理で生成するリサイズ済みのWebP
+    pub fn variant_key(&self, size: ImageSize) -> Self {
+        Self(format!("{}.{}.webp", self.0, size))
+    }
+    // 本人のみがダウンロードできる非公開領域
+    pub fn export_key(user_id: &user::Id, file_name: String) -> Self {
+        Self(format!("export/{}/{}", user_id.as_str(), file_name))
+    }
+    pub fn asset_prefix(user_id: &user::Id) -> String {
+        format!("asset/{}/", user_id.as_str())
+    }
+    // 本人のアセットとエクスポートのみダウンロードできる
+    pub fn is_downloadable_by(&self, user_id: &user::Id) -> bool {
+        let prefixes = [
+            Self::asset_prefix(user_id),
+            format!("export/{}/", user_id.as_str()),
+        ];
+        prefixes.iter().any(|v| self.0.starts_with(v)) && !self.0.split('/').any(|v| v == "..")
+    }
+    pub fn user_prefixes(user_id: &user::Id) -> Vec<String> {
+        vec![
+            Self::asset_prefix(user_id),
+            format!("tmp/{}/", user_id.as_str()),
+            format!("export/{}/", user_id.as_str()),
+        ]
+    }
 }
@@ -16,6 +16,12 @@ impl TryFrom<String> for Email {
         Ok(Email(value))
     }
 }
+impl Email {
+    // 送信停止リストの照合など、大文字小文字を区別せずに比較するための正規形
+    pub fn to_lowercase(&self) -> Self {
+        Self(self.0.to_lowercase())
+    }
+}
 impl FromUnchecked<String> for Email {
     fn from_unchecked(value: String) -> Self {
         Self(value)
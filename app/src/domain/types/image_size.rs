@@ -19,8 +19,4 @@ impl ImageSize {
             ImageSize::Small => 300,
         }
     }
-
-    pub fn query_param(&self) -> String {
-        format!("w={}", self.width())
-    }
 }
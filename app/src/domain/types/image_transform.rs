@@ -0,0 +1,124 @@
+use crate::domain::types::image_size::ImageSize;
+use async_graphql::Enum;
+use strum_macros::{Display, EnumString};
+
+// 任意の値を許すとCDNのキャッシュが効かなくなるため、許可した値のみ受け付ける
+const ALLOWED_DIMENSIONS: &[u32] = &[100, 150, 300, 450, 600, 900, 1200];
+const ALLOWED_QUALITIES: &[u8] = &[50, 75, 90];
+const ALLOWED_DPRS: &[u8] = &[1, 2, 3];
+const DEFAULT_QUALITY: u8 = 75;
+// image-optimizer の MAX_SIZE と合わせる
+const MAX_PIXEL_SIZE: u32 = 2400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, Enum)]
+#[strum(serialize_all = "lowercase")]
+pub enum ImageFit {
+    Cover,
+    Contain,
+    // 縦横とも指定サイズ以上になるように縮小する(切り抜かない)
+    Outside,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, Enum)]
+#[strum(serialize_all = "lowercase")]
+pub enum ImageFormat {
+    Webp,
+    Avif,
+    Jpeg,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageTransform {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fit: ImageFit,
+    pub format: ImageFormat,
+    pub quality: u8,
+    pub dpr: u8,
+}
+
+impl ImageTransform {
+    pub fn new(
+        width: Option<u32>,
+        height: Option<u32>,
+        fit: Option<ImageFit>,
+        format: Option<ImageFormat>,
+        quality: Option<u8>,
+        dpr: Option<u8>,
+    ) -> Result<Self, String> {
+        if width.is_none() && height.is_none() {
+            return Err("幅か高さのどちらかを指定してください".into());
+        }
+        for v in [width, height].into_iter().flatten() {
+            if !ALLOWED_DIMENSIONS.contains(&v) {
+                return Err(format!(
+                    "サイズは{:?}のいずれかを指定してください",
+                    ALLOWED_DIMENSIONS
+                ));
+            }
+        }
+        let quality = quality.unwrap_or(DEFAULT_QUALITY);
+        if !ALLOWED_QUALITIES.contains(&quality) {
+            return Err(format!(
+                "品質は{:?}のいずれかを指定してください",
+                ALLOWED_QUALITIES
+            ));
+        }
+        let dpr = dpr.unwrap_or(1);
+        if !ALLOWED_DPRS.contains(&dpr) {
+            return Err(format!(
+                "DPRは{:?}のいずれかを指定してください",
+                ALLOWED_DPRS
+            ));
+        }
+        if [width, height]
+            .into_iter()
+            .flatten()
+            .any(|v| v * dpr as u32 > MAX_PIXEL_SIZE)
+        {
+            return Err(format!(
+                "サイズとDPRの積は{}以下である必要があります",
+                MAX_PIXEL_SIZE
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            fit: fit.unwrap_or(ImageFit::Contain),
+            format: format.unwrap_or(ImageFormat::Webp),
+            quality,
+            dpr,
+        })
+    }
+
+    // 同じ変換が常に同じURLになるようにパラメータの順序を固定する
+    pub fn query_param(&self) -> String {
+        let mut params = vec![];
+        if let Some(w) = self.width {
+            params.push(format!("w={}", w));
+        }
+        if let Some(h) = self.height {
+            params.push(format!("h={}", h));
+        }
+        params.push(format!("fit={}", self.fit));
+        params.push(format!("fm={}", self.format));
+        params.push(format!("q={}", self.quality));
+        params.push(format!("dpr={}", self.dpr));
+        params.join("&")
+    }
+}
+
+// 旧 image-optimizer の resize(t, t, fit: 'outside') と同じ結果になるようにする
+impl From<ImageSize> for ImageTransform {
+    fn from(size: ImageSize) -> Self {
+        Self {
+            width: Some(size.width()),
+            height: Some(size.width()),
+            fit: ImageFit::Outside,
+            format: ImageFormat::Webp,
+            quality: DEFAULT_QUALITY,
+            dpr: 1,
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use crate::domain::types::email::Email;
+use crate::mail_template::RenderedMail;
+use bytes::Bytes;
+
+// MIMEの組み立て時に設定するため、任意のヘッダーとしては上書きさせない
+const RESERVED_HEADERS: &[&str] = &[
+    "from",
+    "sender",
+    "to",
+    "cc",
+    "bcc",
+    "reply-to",
+    "return-path",
+    "subject",
+    "date",
+    "message-id",
+    "mime-version",
+    "content-type",
+    "content-transfer-encoding",
+    "content-disposition",
+];
+
+#[derive(Debug, Clone)]
+pub struct Attachment {
+    pub filename: String,
+    pub content_type: String,
+    pub data: Bytes,
+}
+impl Attachment {
+    pub fn new(filename: impl Into<String>, content_type: impl Into<String>, data: Bytes) -> Self {
+        Self {
+            filename: filename.into(),
+            content_type: content_type.into(),
+            data,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OutgoingMail {
+    pub to: Vec<Email>,
+    pub cc: Vec<Email>,
+    pub bcc: Vec<Email>,
+    pub reply_to: Vec<Email>,
+    pub subject: String,
+    pub text: String,
+    pub html: Option<String>,
+    pub attachments: Vec<Attachment>,
+    pub headers: Vec<(String, String)>,
+}
+impl OutgoingMail {
+    pub fn new(subject: impl Into<String>, text: impl Into<String>) -> Self {
+        Self {
+            subject: subject.into(),
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn to(mut self, email: Email) -> Self {
+        self.to.push(email);
+        self
+    }
+
+    pub fn cc(mut self, email: Email) -> Self {
+        self.cc.push(email);
+        self
+    }
+
+    pub fn bcc(mut self, email: Email) -> Self {
+        self.bcc.push(email);
+        self
+    }
+
+    pub fn reply_to(mut self, email: Email) -> Self {
+        self.reply_to.push(email);
+        self
+    }
+
+    pub fn html(self, html: impl Into<String>) -> Self {
+        Self {
+            html: Some(html.into()),
+            ..self
+        }
+    }
+
+    pub fn attach(mut self, attachment: Attachment) -> Self {
+        self.attachments.push(attachment);
+        self
+    }
+
+    // List-Unsubscribe など任意のヘッダーを追加する
+    pub fn header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn recipients(&self) -> impl Iterator<Item = &Email> {
+        self.to.iter().chain(self.cc.iter()).chain(self.bcc.iter())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.recipients().next().is_none() {
+            return Err("宛先が指定されていません".into());
+        }
+        let has_line_break = |v: &str| v.contains(['\r', '\n']);
+        if has_line_break(&self.subject) {
+            return Err("件名に改行を含めることはできません".into());
+        }
+        for (name, value) in &self.headers {
+            if name.is_empty()
+                || !name.chars().all(|c| c.is_ascii_graphic() && c != ':')
+                || has_line_break(value)
+                || RESERVED_HEADERS.contains(&name.to_ascii_lowercase().as_str())
+            {
+                return Err(format!("不正なヘッダーです: {}", name));
+            }
+        }
+        for attachment in &self.attachments {
+            if has_line_break(&attachment.filename) || has_line_break(&attachment.content_type) {
+                return Err(format!("不正な添付ファイルです: {}", attachment.filename));
+            }
+        }
+        Ok(())
+    }
+}
+impl From<RenderedMail> for OutgoingMail {
+    fn from(v: RenderedMail) -> Self {
+        Self::new(v.subject, v.text).html(v.html)
+    }
+}
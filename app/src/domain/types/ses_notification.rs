@@ -0,0 +1,48 @@
+use serde::Deserialize;
+
+// SNS経由で届くSESの通知。通知設定(notificationType)とイベント発行(eventType)の両方を受け付ける
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SesNotification {
+    #[serde(alias = "eventType")]
+    pub notification_type: NotificationType,
+    pub bounce: Option<Bounce>,
+    pub complaint: Option<Complaint>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+pub enum NotificationType {
+    Bounce,
+    Complaint,
+    Delivery,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bounce {
+    // Permanent / Transient / Undetermined
+    pub bounce_type: String,
+    pub bounce_sub_type: Option<String>,
+    pub bounced_recipients: Vec<Recipient>,
+}
+impl Bounce {
+    pub fn is_permanent(&self) -> bool {
+        self.bounce_type == "Permanent"
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Complaint {
+    pub complained_recipients: Vec<Recipient>,
+    pub complaint_feedback_type: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recipient {
+    pub email_address: String,
+    pub diagnostic_code: Option<String>,
+}
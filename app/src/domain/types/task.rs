@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 // SNS event types for Lambda
 #[derive(Serialize, Deserialize)]
@@ -15,6 +16,90 @@ pub struct SnsRecord {
 pub struct SnsMessage {
     #[serde(rename = "Message")]
     pub message: String,
+    #[serde(rename = "MessageAttributes", default)]
+    pub message_attributes: HashMap<String, SnsMessageAttribute>,
+}
+impl SnsMessage {
+    // 属性なしで発行されたメッセージでは None
+    pub fn task_attributes(&self) -> Result<Option<TaskAttributes>, String> {
+        let get = |name: &str| self.message_attributes.get(name).map(|v| v.value.clone());
+        let Some(task_type) = get(TaskAttributes::TASK_TYPE) else {
+            return Ok(None);
+        };
+        let priority = match get(TaskAttributes::PRIORITY) {
+            Some(v) => v
+                .parse()
+                .map_err(|e| format!("invalid priority {}: {:?}", v, e))?,
+            None => Priority::default(),
+        };
+        Ok(Some(TaskAttributes {
+            task_type,
+            priority,
+            tenant: get(TaskAttributes::TENANT),
+        }))
+    }
+}
+#[derive(Serialize, Deserialize)]
+pub struct SnsMessageAttribute {
+    #[serde(rename = "Type")]
+    pub data_type: String,
+    #[serde(rename = "Value")]
+    pub value: String,
+}
+
+// 発行時に付与するメッセージ属性。サブスクリプションのフィルターポリシーで振り分けに使う
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaskAttributes {
+    pub task_type: String,
+    pub priority: Priority,
+    pub tenant: Option<String>,
+}
+impl TaskAttributes {
+    pub const TASK_TYPE: &'static str = "task_type";
+    pub const PRIORITY: &'static str = "priority";
+    pub const TENANT: &'static str = "tenant";
+
+    pub fn new(payload: &AsyncTaskPayload) -> Self {
+        Self {
+            task_type: payload.task_type().to_string(),
+            priority: Priority::default(),
+            tenant: None,
+        }
+    }
+
+    pub fn priority(self, priority: Priority) -> Self {
+        Self { priority, ..self }
+    }
+
+    pub fn tenant(self, tenant: impl Into<String>) -> Self {
+        Self {
+            tenant: Some(tenant.into()),
+            ..self
+        }
+    }
+
+    // 属性名と値。値はすべて String 型で送る
+    pub fn to_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = vec![
+            (Self::TASK_TYPE, self.task_type.clone()),
+            (Self::PRIORITY, self.priority.to_string()),
+        ];
+        if let Some(tenant) = &self.tenant {
+            pairs.push((Self::TENANT, tenant.clone()));
+        }
+        pairs
+    }
+}
+
+#[derive(
+    Clone, Copy, Debug, Default, Eq, PartialEq, strum_macros::EnumString, strum_macros::Display,
+)]
+#[strum(serialize_all = "lowercase")]
+pub enum Priority {
+    High,
+    #[default]
+    Normal,
+    Low,
 }
 
 // SQS event types for Lambda
@@ -29,9 +114,92 @@ pub struct SqsRecord {
 }
 
 // Async task types
+// キューに投入するメッセージ。同じ idempotency_key のメッセージは一度だけ処理される
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct AsyncTaskPayload {
-    pub name: String,
+#[serde(from = "AsyncTaskMessageRepr")]
+pub struct AsyncTaskMessage {
+    // キー導入前に投入されたメッセージでは未設定
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    #[serde(flatten)]
+    pub payload: AsyncTaskPayload,
+}
+// type 導入前の {"name": ...} 形式のメッセージがキューやDLQに残っていても Sample として読めるようにする
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AsyncTaskMessageRepr {
+    Current {
+        #[serde(default)]
+        idempotency_key: Option<String>,
+        #[serde(flatten)]
+        payload: AsyncTaskPayload,
+    },
+    Legacy(LegacyAsyncTaskPayload),
+}
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LegacyAsyncTaskPayload {
+    name: String,
+}
+impl From<AsyncTaskMessageRepr> for AsyncTaskMessage {
+    fn from(v: AsyncTaskMessageRepr) -> Self {
+        match v {
+            AsyncTaskMessageRepr::Current {
+                idempotency_key,
+                payload,
+            } => Self {
+                idempotency_key,
+                payload,
+            },
+            AsyncTaskMessageRepr::Legacy(legacy) => Self {
+                idempotency_key: None,
+                payload: AsyncTaskPayload::Sample { name: legacy.name },
+            },
+        }
+    }
+}
+impl AsyncTaskMessage {
+    pub fn new(payload: AsyncTaskPayload) -> Self {
+        Self::with_key(payload, crate::domain::generate_id_str())
+    }
+
+    // 同じ処理を二重に投入しうる場合は、処理対象から決まるキーを指定する
+    pub fn with_key(payload: AsyncTaskPayload, key: impl Into<String>) -> Self {
+        Self {
+            idempotency_key: Some(key.into()),
+            payload,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, strum_macros::IntoStaticStr)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AsyncTaskPayload {
+    Sample { name: String },
+    DeleteUser { deletion_request_id: String },
+    ExportUserData { data_export_id: String },
+    ProcessImage { asset_id: String },
+    // scheduled_tasks に保存したタスクを実行する
+    Scheduled { scheduled_task_id: String },
+}
+impl AsyncTaskPayload {
+    pub fn task_type(&self) -> &'static str {
+        self.into()
+    }
+
+    // FIFOキューでは同じグループ内でのみ順序が保証される
+    pub fn group_id(&self) -> &str {
+        match self {
+            AsyncTaskPayload::Sample { name } => name,
+            AsyncTaskPayload::DeleteUser {
+                deletion_request_id,
+            } => deletion_request_id,
+            AsyncTaskPayload::ExportUserData { data_export_id } => data_export_id,
+            AsyncTaskPayload::ProcessImage { asset_id } => asset_id,
+            AsyncTaskPayload::Scheduled { scheduled_task_id } => scheduled_task_id,
+        }
+    }
 }
 
 // Sync task types
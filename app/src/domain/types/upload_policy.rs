@@ -0,0 +1,67 @@
+use async_graphql::Enum;
+use strum_macros::{Display, EnumString};
+
+const DEFAULT_EXPIRES_IN_SECS: u64 = 15 * 60;
+const MAX_EXPIRES_IN_SECS: u64 = 60 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, Enum)]
+pub enum UploadPurpose {
+    Image,
+    Document,
+}
+
+impl UploadPurpose {
+    pub fn allowed_content_types(&self) -> &'static [&'static str] {
+        match self {
+            UploadPurpose::Image => &["image/jpeg", "image/png", "image/webp", "image/gif"],
+            UploadPurpose::Document => &["application/pdf", "text/csv", "text/plain"],
+        }
+    }
+
+    pub fn max_size(&self) -> u64 {
+        match self {
+            UploadPurpose::Image => 10 * 1024 * 1024,
+            UploadPurpose::Document => 20 * 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UploadPolicy {
+    pub content_type: String,
+    pub min_size: u64,
+    pub max_size: u64,
+    pub expires_in_secs: u64,
+}
+
+impl UploadPolicy {
+    pub fn new(
+        purpose: UploadPurpose,
+        content_type: String,
+        expires_in_secs: Option<u64>,
+    ) -> Result<Self, String> {
+        if !purpose
+            .allowed_content_types()
+            .contains(&content_type.as_str())
+        {
+            return Err(format!(
+                "{}はアップロードできないファイル形式です",
+                content_type
+            ));
+        }
+        let expires_in_secs = expires_in_secs.unwrap_or(DEFAULT_EXPIRES_IN_SECS);
+        if expires_in_secs == 0 || expires_in_secs > MAX_EXPIRES_IN_SECS {
+            return Err(format!(
+                "有効期限は1秒以上{}秒以下である必要があります",
+                MAX_EXPIRES_IN_SECS
+            ));
+        }
+
+        Ok(Self {
+            content_type,
+            min_size: 1,
+            max_size: purpose.max_size(),
+            expires_in_secs,
+        })
+    }
+}
@@ -1,3 +1,6 @@
+pub mod data_export;
+pub mod deletion_request;
+
 use crate::AppResult;
 use crate::adapter::DbConn;
 use crate::domain::HasId;
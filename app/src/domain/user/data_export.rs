@@ -0,0 +1,108 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::types::asset_key::AssetKey;
+use crate::domain::types::time::{LocalDateTime, now};
+use crate::domain::{HasId, user};
+use async_trait::async_trait;
+
+pub type Id = crate::domain::Id<DataExport>;
+#[derive(Debug, Clone)]
+pub struct DataExport {
+    pub id: Id,
+    pub user_id: user::Id,
+    pub status: Status,
+    pub object_key: Option<AssetKey>,
+    pub last_error: Option<String>,
+    pub completed_at: Option<LocalDateTime>,
+    pub created_at: LocalDateTime,
+    pub updated_at: LocalDateTime,
+}
+impl DataExport {
+    pub fn new(user_id: user::Id) -> Self {
+        Self {
+            id: Id::generate(),
+            user_id,
+            status: Status::Pending,
+            object_key: None,
+            last_error: None,
+            completed_at: None,
+            created_at: now(),
+            updated_at: now(),
+        }
+    }
+
+    pub fn start(self) -> Self {
+        Self {
+            status: Status::Processing,
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    pub fn complete(self, object_key: AssetKey) -> Self {
+        Self {
+            status: Status::Completed,
+            object_key: Some(object_key),
+            last_error: None,
+            completed_at: Some(now()),
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    pub fn fail(self, error: String) -> Self {
+        Self {
+            status: Status::Failed,
+            last_error: Some(error),
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    pub fn is_in_progress(&self) -> bool {
+        matches!(self.status, Status::Pending | Status::Processing)
+    }
+}
+impl HasId for DataExport {
+    type Entity = Self;
+    fn id(&self) -> &crate::domain::Id<Self> {
+        &self.id
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    async_graphql::Enum,
+)]
+pub enum Status {
+    Pending,
+    Processing,
+    Completed,
+    Failed,
+}
+impl TryFrom<String> for Status {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse().map_err(|e| format!("error: {:?}", e))
+    }
+}
+impl Into<String> for Status {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+#[async_trait]
+pub trait DataExportRepository: Send + Sync {
+    async fn find_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<Vec<DataExport>>;
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<DataExport>;
+    async fn insert(&self, db: DbConn<'_>, data_export: DataExport) -> AppResult<()>;
+    async fn update(&self, db: DbConn<'_>, data_export: DataExport) -> AppResult<()>;
+    async fn delete_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<()>;
+}
@@ -0,0 +1,169 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::types::time::{LocalDateTime, now};
+use crate::domain::{HasId, user};
+use async_trait::async_trait;
+
+pub type Id = crate::domain::Id<DeletionRequest>;
+#[derive(Debug, Clone)]
+pub struct DeletionRequest {
+    pub id: Id,
+    pub user_id: user::Id,
+    pub status: Status,
+    pub step: Step,
+    pub attempts: u32,
+    pub last_error: Option<String>,
+    pub completed_at: Option<LocalDateTime>,
+    pub created_at: LocalDateTime,
+    pub updated_at: LocalDateTime,
+}
+impl DeletionRequest {
+    pub fn new(user_id: user::Id) -> Self {
+        Self {
+            id: Id::generate(),
+            user_id,
+            status: Status::Pending,
+            step: Step::Orders,
+            attempts: 0,
+            last_error: None,
+            completed_at: None,
+            created_at: now(),
+            updated_at: now(),
+        }
+    }
+
+    pub fn start(self) -> Self {
+        Self {
+            status: Status::Processing,
+            attempts: self.attempts + 1,
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    pub fn advance(self) -> Self {
+        let step = self.step.next();
+        let completed = step == Step::Done;
+        Self {
+            step,
+            status: if completed {
+                Status::Completed
+            } else {
+                self.status
+            },
+            last_error: if completed { None } else { self.last_error },
+            completed_at: if completed { Some(now()) } else { None },
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    pub fn fail(self, error: String) -> Self {
+        Self {
+            status: Status::Failed,
+            last_error: Some(error),
+            updated_at: now(),
+            ..self
+        }
+    }
+
+    pub fn is_in_progress(&self) -> bool {
+        matches!(self.status, Status::Pending | Status::Processing)
+    }
+}
+impl HasId for DeletionRequest {
+    type Entity = Self;
+    fn id(&self) -> &crate::domain::Id<Self> {
+        &self.id
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    async_graphql::Enum,
+)]
+pub enum Status {
+    Pending,
+    Processing,
+    Completed,
+    Failed,
+}
+impl TryFrom<String> for Status {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse().map_err(|e| format!("error: {:?}", e))
+    }
+}
+impl Into<String> for Status {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+// 退会処理は各ステップを冪等に実行し、リトライ時は記録済みのステップから再開する
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    strum_macros::EnumString,
+    strum_macros::Display,
+    async_graphql::Enum,
+)]
+pub enum Step {
+    Orders,
+    Storage,
+    Identity,
+    Profile,
+    Done,
+}
+impl Step {
+    pub fn next(&self) -> Self {
+        match self {
+            Step::Orders => Step::Storage,
+            Step::Storage => Step::Identity,
+            Step::Identity => Step::Profile,
+            Step::Profile => Step::Done,
+            Step::Done => Step::Done,
+        }
+    }
+}
+impl TryFrom<String> for Step {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse().map_err(|e| format!("error: {:?}", e))
+    }
+}
+impl Into<String> for Step {
+    fn into(self) -> String {
+        self.to_string()
+    }
+}
+
+// 退会ユーザーの注文の扱い
+#[derive(Debug, Clone, Copy, Eq, PartialEq, strum_macros::EnumString, strum_macros::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum OrderPolicy {
+    Delete,
+    Anonymize,
+}
+
+#[async_trait]
+pub trait DeletionRequestRepository: Send + Sync {
+    async fn find(&self, db: DbConn<'_>) -> AppResult<Vec<DeletionRequest>>;
+    async fn find_by_user(
+        &self,
+        db: DbConn<'_>,
+        user_id: &user::Id,
+    ) -> AppResult<Vec<DeletionRequest>>;
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<DeletionRequest>;
+    async fn insert(&self, db: DbConn<'_>, request: DeletionRequest) -> AppResult<()>;
+    async fn update(&self, db: DbConn<'_>, request: DeletionRequest) -> AppResult<()>;
+}
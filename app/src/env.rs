@@ -1,3 +1,4 @@
+use crate::domain::user::deletion_request::OrderPolicy;
 use google_identitytoolkit3::yup_oauth2 as oauth2;
 use std::str::FromStr;
 
@@ -5,6 +6,14 @@ fn must_env(k: &str) -> String {
     std::env::var(k).expect(format!("env {} missing", k).as_str())
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, strum_macros::EnumString, strum_macros::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum StorageDriver {
+    S3,
+    Local,
+    Memory,
+}
+
 #[derive(Debug, Clone)]
 pub struct Env {
     pub env: String,
@@ -12,11 +21,21 @@ pub struct Env {
     pub with_lambda: bool,
     pub database_url: String,
     pub s3_bucket_name: String,
+    pub storage_driver: StorageDriver,
+    pub local_storage_dir: String,
+    pub local_storage_base_url: String,
+    pub local_storage_signing_key: String,
     pub from_email_address: String,
+    pub local_mail_dir: String,
     pub sns_async_task_topic_arn: String,
     pub sqs_async_task_queue_url: String,
+    // DLQの確認・再投入を行う場合は必須
+    pub sqs_async_task_dlq_url: Option<String>,
     pub sync_task_lambda_arn: String,
+    pub remote_function_timeout_secs: u64,
     pub cognito_admin_user_pool_id: String,
+    pub user_deletion_order_policy: OrderPolicy,
+    pub tmp_upload_retention_hours: i64,
 
     // Google Cloud関連を使う場合は必須
     pub google_project_id: Option<String>,
@@ -26,6 +45,9 @@ pub struct Env {
     pub cloudfront_domain: Option<String>,
     pub cloudfront_key_pair_id: Option<String>,
     pub cloudfront_private_key: Option<String>,
+    // 設定されている場合のみキャッシュの無効化を行う
+    pub cloudfront_distribution_id: Option<String>,
+    pub cloudfront_cookie_domain: Option<String>,
 
     pub sentry_dsn: String,
 }
@@ -48,19 +70,49 @@ impl Env {
                 None
             };
 
+        let port = std::env::var("PORT").unwrap_or("8080".to_string());
+
         Env {
             env: must_env("ENV"),
-            port: std::env::var("PORT").unwrap_or("8080".to_string()),
+            port: port.clone(),
             with_lambda: std::env::var("WITH_LAMBDA")
                 .map(|v| bool::from_str(&v).expect("failed to parse WITH_LAMBDA"))
                 .unwrap_or(false),
             database_url: must_env("DATABASE_URL"),
             s3_bucket_name: must_env("S3_BUCKET_NAME"),
+            storage_driver: std::env::var("STORAGE_DRIVER")
+                .map(|v| StorageDriver::from_str(&v).expect("failed to parse STORAGE_DRIVER"))
+                .unwrap_or(StorageDriver::S3),
+            local_storage_dir: std::env::var("LOCAL_STORAGE_DIR").unwrap_or(".storage".to_string()),
+            local_storage_base_url: std::env::var("LOCAL_STORAGE_BASE_URL")
+                .unwrap_or(format!("http://localhost:{}", port)),
+            local_storage_signing_key: std::env::var("LOCAL_STORAGE_SIGNING_KEY")
+                .unwrap_or("local-storage".to_string()),
             from_email_address: must_env("FROM_EMAIL_ADDRESS"),
+            local_mail_dir: std::env::var("LOCAL_MAIL_DIR").unwrap_or(".mail".to_string()),
             sns_async_task_topic_arn: must_env("SNS_ASYNC_TASK_TOPIC_ARN"),
             sqs_async_task_queue_url: must_env("SQS_ASYNC_TASK_QUEUE_URL"),
+            sqs_async_task_dlq_url: std::env::var("SQS_ASYNC_TASK_DLQ_URL").ok(),
             sync_task_lambda_arn: std::env::var("SYNC_TASK_LAMBDA_ARN").unwrap_or("".to_string()), // TODO: input target lambda arn
+            // API Gatewayの統合タイムアウト(29秒)より短くする
+            remote_function_timeout_secs: std::env::var("REMOTE_FUNCTION_TIMEOUT_SECS")
+                .map(|v| {
+                    v.parse()
+                        .expect("failed to parse REMOTE_FUNCTION_TIMEOUT_SECS")
+                })
+                .unwrap_or(25),
             cognito_admin_user_pool_id: must_env("COGNITO_ADMIN_USER_POOL_ID"),
+            user_deletion_order_policy: std::env::var("USER_DELETION_ORDER_POLICY")
+                .map(|v| {
+                    OrderPolicy::from_str(&v).expect("failed to parse USER_DELETION_ORDER_POLICY")
+                })
+                .unwrap_or(OrderPolicy::Delete),
+            tmp_upload_retention_hours: std::env::var("TMP_UPLOAD_RETENTION_HOURS")
+                .map(|v| {
+                    v.parse()
+                        .expect("failed to parse TMP_UPLOAD_RETENTION_HOURS")
+                })
+                .unwrap_or(24),
 
             // Google Cloud関連を使う場合は必須
             google_project_id: std::env::var("GOOGLE_PROJECT_ID").ok(),
@@ -70,6 +122,8 @@ impl Env {
             cloudfront_domain: std::env::var("CLOUDFRONT_DOMAIN").ok(),
             cloudfront_key_pair_id: std::env::var("CLOUDFRONT_KEY_PAIR_ID").ok(),
             cloudfront_private_key: std::env::var("CLOUDFRONT_PRIVATE_KEY").ok(),
+            cloudfront_distribution_id: std::env::var("CLOUDFRONT_DISTRIBUTION_ID").ok(),
+            cloudfront_cookie_domain: std::env::var("CLOUDFRONT_COOKIE_DOMAIN").ok(),
 
             sentry_dsn: must_env("SENTRY_DSN"),
         }
@@ -11,6 +11,8 @@ pub enum Kind {
     Forbidden,
     NotFound,
     Duplicate,
+    // 送信停止リストに登録されたアドレスへのメール送信
+    Suppressed,
     Internal,
 }
 
@@ -23,6 +25,7 @@ impl Kind {
             Kind::Forbidden => "アクセスが許可されていません",
             Kind::NotFound => "リソースが見つかりません",
             Kind::Duplicate => "重複するリソースが存在します",
+            Kind::Suppressed => "送信停止中のメールアドレスです",
             Kind::Internal => "内部エラーが発生しました",
         };
 
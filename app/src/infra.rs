@@ -1,8 +1,15 @@
+pub mod batch_publish;
 pub mod cloudfront;
 pub mod cognito;
 pub mod firebase;
 pub mod lambda;
+pub mod local_mail;
+pub mod local_queue;
+pub mod local_storage;
 pub mod log;
+pub mod mail_suppression;
+pub mod message_attributes;
+pub mod mime;
 pub mod rdb;
 pub mod s3;
 pub mod sentry;
@@ -0,0 +1,203 @@
+use crate::AppResult;
+use crate::adapter::{BatchEntry, BatchPublishFailure, BatchPublishResult, MAX_PUBLISH_BATCH_SIZE};
+use crate::domain::types::task::TaskAttributes;
+use crate::errors::AppError;
+use crate::errors::Kind::Internal;
+use aws_sdk_sqs::config::http::HttpResponse;
+use aws_sdk_sqs::error::{ProvideErrorMetadata, SdkError};
+use std::future::Future;
+use std::time::Duration;
+
+const MAX_RETRIES: u32 = 2;
+// 件数に加えて、1回のリクエストに含めるメッセージと属性の合計サイズにも上限がある
+const MAX_BATCH_BYTES: usize = 256 * 1024;
+const THROTTLING_CODES: &[&str] = &[
+    "Throttling",
+    "ThrottlingException",
+    "Throttled",
+    "RequestThrottled",
+    "TooManyRequestsException",
+    "KMSThrottlingException",
+];
+
+// id はバッチ内でエントリを識別するためのもので、入力での位置を使う
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub id: String,
+    pub body: String,
+    pub group_id: Option<String>,
+    pub deduplication_id: Option<String>,
+    pub attributes: Option<TaskAttributes>,
+}
+impl Entry {
+    fn size(&self) -> usize {
+        let attributes = self
+            .attributes
+            .as_ref()
+            .map(|v| {
+                v.to_pairs()
+                    .iter()
+                    .map(|(name, value)| name.len() + "String".len() + value.len())
+                    .sum()
+            })
+            .unwrap_or(0);
+        self.body.len() + attributes
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EntryFailure {
+    pub id: String,
+    pub code: String,
+    pub message: Option<String>,
+    pub sender_fault: bool,
+}
+
+// バッチのリクエスト自体の失敗。スロットリングとサーバー側のエラーのみリトライする
+// sender_fault はスロットリング以外のクライアントエラー(4xx)の場合のみ立てる
+#[derive(Debug)]
+pub struct RequestError {
+    error: AppError,
+    retryable: bool,
+    sender_fault: bool,
+}
+impl From<AppError> for RequestError {
+    fn from(error: AppError) -> Self {
+        Self {
+            error,
+            retryable: false,
+            sender_fault: false,
+        }
+    }
+}
+// SdkError は各SDKで共通の型なので、SNSのエラーもここで扱える
+impl<E> From<SdkError<E, HttpResponse>> for RequestError
+where
+    E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+{
+    fn from(err: SdkError<E, HttpResponse>) -> Self {
+        let (retryable, sender_fault) = match &err {
+            SdkError::TimeoutError(_) | SdkError::DispatchFailure(_) => (true, false),
+            SdkError::ResponseError(v) => {
+                let status = v.raw().status();
+                (status.is_server_error(), status.is_client_error())
+            }
+            SdkError::ServiceError(v) => {
+                let status = v.raw().status();
+                let throttled = v
+                    .err()
+                    .code()
+                    .map(|code| THROTTLING_CODES.contains(&code))
+                    .unwrap_or(false);
+                (
+                    status.is_server_error() || throttled,
+                    status.is_client_error() && !throttled,
+                )
+            }
+            _ => (false, false),
+        };
+        Self {
+            error: Internal.from_src(err),
+            retryable,
+            sender_fault,
+        }
+    }
+}
+
+// SQSとSNSで共通のチャンク分割とリトライ。send は失敗したエントリを返す
+pub async fn publish<F, Fut>(entries: Vec<BatchEntry>, send: F) -> AppResult<BatchPublishResult>
+where
+    F: Fn(Vec<Entry>) -> Fut,
+    Fut: Future<Output = Result<Vec<EntryFailure>, RequestError>>,
+{
+    let total = entries.len();
+    let mut pending = entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, v)| {
+            Ok(Entry {
+                id: index.to_string(),
+                body: serde_json::to_string(&v.input).map_err(Internal.from_srcf())?,
+                group_id: v.group_id,
+                deduplication_id: v.deduplication_id,
+                attributes: v.attributes,
+            })
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+
+    let mut failures = vec![];
+    for attempt in 0..=MAX_RETRIES {
+        if attempt > 0 {
+            tokio::time::sleep(Duration::from_millis(200 * 2u64.pow(attempt))).await;
+        }
+
+        let mut retryable = vec![];
+        for chunk in chunks(&pending) {
+            let chunk_failures = match send(chunk.to_vec()).await {
+                Ok(v) => v,
+                // リクエスト自体の失敗はチャンク内の全エントリの失敗として扱う
+                Err(err) => {
+                    let chunk_failures = chunk
+                        .iter()
+                        .map(|v| EntryFailure {
+                            id: v.id.clone(),
+                            code: "RequestFailed".to_string(),
+                            message: Some(err.error.to_string()),
+                            sender_fault: err.sender_fault,
+                        })
+                        .collect::<Vec<_>>();
+                    if !err.retryable {
+                        failures.extend(chunk_failures);
+                        continue;
+                    }
+                    chunk_failures
+                }
+            };
+            for failure in chunk_failures {
+                if failure.sender_fault || attempt == MAX_RETRIES {
+                    failures.push(failure);
+                } else if let Some(entry) = chunk.iter().find(|v| v.id == failure.id) {
+                    retryable.push(entry.clone());
+                }
+            }
+        }
+        if retryable.is_empty() {
+            break;
+        }
+        pending = retryable;
+    }
+
+    let failures = failures
+        .into_iter()
+        .map(|v| BatchPublishFailure {
+            index: v.id.parse().unwrap_or_default(),
+            code: v.code,
+            message: v.message,
+            sender_fault: v.sender_fault,
+        })
+        .collect::<Vec<_>>();
+    Ok(BatchPublishResult {
+        success_count: total - failures.len(),
+        failures,
+    })
+}
+
+// 件数と合計サイズの上限を超えないように分ける。上限を超える単独のエントリはそのまま送り、失敗として返す
+fn chunks(entries: &[Entry]) -> Vec<&[Entry]> {
+    let mut chunks = vec![];
+    let mut start = 0;
+    let mut bytes = 0;
+    for (i, entry) in entries.iter().enumerate() {
+        let size = entry.size();
+        if i > start && (i - start >= MAX_PUBLISH_BATCH_SIZE || bytes + size > MAX_BATCH_BYTES) {
+            chunks.push(&entries[start..i]);
+            start = i;
+            bytes = 0;
+        }
+        bytes += size;
+    }
+    if start < entries.len() {
+        chunks.push(&entries[start..]);
+    }
+    chunks
+}
@@ -1,40 +1,58 @@
 use crate::AppResult;
-use crate::adapter::ImageCdn;
+use crate::adapter::{ImageCdn, SignedCookies};
 use crate::domain::types::asset_key::AssetKey;
-use crate::domain::types::image_size::ImageSize;
+use crate::domain::types::image_transform::ImageTransform;
+use crate::domain::types::time::{FromTimestamp, LocalDateTime};
 use crate::errors::Kind::*;
 use async_graphql::async_trait::async_trait;
+use aws_sdk_cloudfront::Client;
+use aws_sdk_cloudfront::types::{InvalidationBatch, Paths};
 use base64::prelude::*;
-use cloudfront_sign::{SignedOptions, get_signed_url};
+use cloudfront_sign::{SignedOptions, get_signed_cookie, get_signed_url};
 use http::Uri;
 use std::borrow::Cow;
 use std::time::Duration;
 
+const COOKIE_EXPIRES_IN_SECS: u64 = 60 * 60;
+
 #[derive(Clone)]
 pub struct Adapter {
+    client: Client,
     domain: String,
     key_pair_id: String,
     private_key: String,
+    distribution_id: Option<String>,
+    cookie_domain: Option<String>,
 }
 
 impl Adapter {
-    pub fn new(domain: String, key_pair_id: String, private_key_base64: String) -> AppResult<Self> {
+    pub fn new(
+        client: Client,
+        domain: String,
+        key_pair_id: String,
+        private_key_base64: String,
+        distribution_id: Option<String>,
+        cookie_domain: Option<String>,
+    ) -> AppResult<Self> {
         let private_key_bytes = BASE64_STANDARD
             .decode(&private_key_base64)
             .map_err(Internal.from_srcf())?;
         let private_key = String::from_utf8(private_key_bytes).map_err(Internal.from_srcf())?;
 
         Ok(Self {
+            client,
             domain,
             key_pair_id,
             private_key,
+            distribution_id,
+            cookie_domain,
         })
     }
 }
 
 #[async_trait]
 impl ImageCdn for Adapter {
-    async fn presign_for_get(&self, key: &AssetKey, size: ImageSize) -> AppResult<Uri> {
+    async fn presign_for_get(&self, key: &AssetKey, transform: &ImageTransform) -> AppResult<Uri> {
         let expires_in = Duration::from_secs(60 * 60);
         let expires_at = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -43,7 +61,7 @@ impl ImageCdn for Adapter {
             + expires_in.as_secs();
 
         let base_url = format!("https://{}/{}", self.domain, key.to_string());
-        let url_with_size = format!("{}?{}", base_url, size.query_param());
+        let url_with_size = format!("{}?{}", base_url, transform.query_param());
 
         let options = SignedOptions {
             key_pair_id: Cow::Owned(self.key_pair_id.clone()),
@@ -56,4 +74,70 @@ impl ImageCdn for Adapter {
 
         signed_url.parse().map_err(Internal.from_srcf())
     }
+
+    async fn sign_cookies(&self, path_prefix: &str) -> AppResult<SignedCookies> {
+        let expires_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            + COOKIE_EXPIRES_IN_SECS;
+
+        // カスタムポリシーでワイルドカードを指定し、prefix配下をまとめて許可する
+        let resource = format!(
+            "https://{}/{}*",
+            self.domain,
+            path_prefix.trim_start_matches('/')
+        );
+        let options = SignedOptions {
+            key_pair_id: Cow::Owned(self.key_pair_id.clone()),
+            private_key: Cow::Owned(self.private_key.clone()),
+            date_less_than: expires_at,
+            ..Default::default()
+        };
+        let mut cookies = get_signed_cookie(&resource, &options)
+            .map_err(Internal.from_srcf())?
+            .into_iter()
+            .collect::<Vec<_>>();
+        cookies.sort();
+
+        Ok(SignedCookies {
+            cookies,
+            domain: self.cookie_domain.clone(),
+            path: "/".to_string(),
+            expires_at: LocalDateTime::from_timestamp(expires_at as i64)
+                .map_err(Internal.withf())?,
+        })
+    }
+
+    async fn invalidate(&self, paths: Vec<String>) -> AppResult<()> {
+        let Some(distribution_id) = &self.distribution_id else {
+            tracing::warn!("cloudfront distribution id is not configured, skip invalidation");
+            return Ok(());
+        };
+        if paths.is_empty() {
+            return Ok(());
+        }
+
+        let quantity = paths.len() as i32;
+        self.client
+            .create_invalidation()
+            .distribution_id(distribution_id)
+            .invalidation_batch(
+                InvalidationBatch::builder()
+                    .paths(
+                        Paths::builder()
+                            .quantity(quantity)
+                            .set_items(Some(paths))
+                            .build()
+                            .map_err(Internal.from_srcf())?,
+                    )
+                    .caller_reference(hex::encode(rand::random::<[u8; 16]>()))
+                    .build()
+                    .map_err(Internal.from_srcf())?,
+            )
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(())
+    }
 }
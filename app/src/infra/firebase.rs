@@ -1 +1,34 @@
 pub mod auth;
+pub mod messaging;
+
+use google_identitytoolkit3::common::{Delegate, Response, Retry};
+use google_identitytoolkit3::hyper_util;
+use std::time::Duration;
+
+// Google APIの一時的なエラー(5xx/429)を指数バックオフでリトライする
+#[derive(Default)]
+pub struct RetryOnTransientError {
+    attempts: u32,
+}
+impl RetryOnTransientError {
+    fn next_backoff(&mut self) -> Retry {
+        if self.attempts >= 2 {
+            return Retry::Abort;
+        }
+        self.attempts += 1;
+        Retry::After(Duration::from_millis(200 * 2u64.pow(self.attempts)))
+    }
+}
+impl Delegate for RetryOnTransientError {
+    fn http_error(&mut self, _err: &hyper_util::client::legacy::Error) -> Retry {
+        self.next_backoff()
+    }
+
+    fn http_failure(&mut self, res: &Response, _err: Option<&serde_json::Value>) -> Retry {
+        if res.status().is_server_error() || res.status().as_u16() == 429 {
+            self.next_backoff()
+        } else {
+            Retry::Abort
+        }
+    }
+}
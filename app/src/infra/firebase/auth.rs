@@ -1,18 +1,17 @@
 use crate::adapter::{UserAuth, UserPrincipal};
 use crate::errors::Kind::{BadRequest, Internal, NotFound};
+use crate::infra::firebase::RetryOnTransientError;
 use crate::{AppResult, domain};
 use async_graphql::async_trait::async_trait;
 use google_identitytoolkit3::api::{
     IdentitytoolkitRelyingpartyDeleteAccountRequest,
     IdentitytoolkitRelyingpartyGetAccountInfoRequest,
 };
-use google_identitytoolkit3::common::{Delegate, Response, Retry};
 use google_identitytoolkit3::{hyper_rustls, hyper_util};
 use jsonwebtoken::{DecodingKey, Validation};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
-use std::time::Duration;
 
 pub type IdentityToolkit = google_identitytoolkit3::IdentityToolkit<
     hyper_rustls::HttpsConnector<hyper_util::client::legacy::connect::HttpConnector>,
@@ -132,33 +131,6 @@ impl UserAuth for Adapter {
     }
 }
 
-#[derive(Default)]
-struct RetryOnTransientError {
-    attempts: u32,
-}
-impl RetryOnTransientError {
-    fn next_backoff(&mut self) -> Retry {
-        if self.attempts >= 2 {
-            return Retry::Abort;
-        }
-        self.attempts += 1;
-        Retry::After(Duration::from_millis(200 * 2u64.pow(self.attempts)))
-    }
-}
-impl Delegate for RetryOnTransientError {
-    fn http_error(&mut self, _err: &hyper_util::client::legacy::Error) -> Retry {
-        self.next_backoff()
-    }
-
-    fn http_failure(&mut self, res: &Response, _err: Option<&serde_json::Value>) -> Retry {
-        if res.status().is_server_error() || res.status().as_u16() == 429 {
-            self.next_backoff()
-        } else {
-            Retry::Abort
-        }
-    }
-}
-
 async fn fetch_jwks() -> AppResult<HashMap<String, Jwk>> {
     Ok(reqwest::get(
         "https://www.googleapis.com/service_accounts/v1/jwk/securetoken@system.gserviceaccount.com",
@@ -0,0 +1,119 @@
+use crate::AppResult;
+use crate::adapter::{MulticastResult, PushMessage, PushNotifier};
+use crate::errors::Kind::Internal;
+use crate::infra::firebase::RetryOnTransientError;
+use async_graphql::async_trait::async_trait;
+use futures_util::{StreamExt, stream};
+use google_fcm1::api::{Message, Notification, SendMessageRequest};
+use google_fcm1::{hyper_rustls, hyper_util};
+
+pub type FirebaseCloudMessaging = google_fcm1::FirebaseCloudMessaging<
+    hyper_rustls::HttpsConnector<hyper_util::client::legacy::connect::HttpConnector>,
+>;
+
+// FCM v1 にはまとめて送るAPIがないため、同時実行数を絞って1件ずつ送る
+const MULTICAST_CONCURRENCY: usize = 10;
+
+#[derive(Clone)]
+pub struct Adapter {
+    messaging: FirebaseCloudMessaging,
+    project_id: String,
+}
+impl Adapter {
+    pub fn new(project_id: String, messaging: FirebaseCloudMessaging) -> Self {
+        Self {
+            messaging,
+            project_id,
+        }
+    }
+
+    async fn send(&self, message: Message) -> Result<(), google_fcm1::Error> {
+        let mut delegate = RetryOnTransientError::default();
+        self.messaging
+            .projects()
+            .messages_send(
+                SendMessageRequest {
+                    message: Some(message),
+                    validate_only: None,
+                },
+                &format!("projects/{}", self.project_id),
+            )
+            .delegate(&mut delegate)
+            .doit()
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PushNotifier for Adapter {
+    async fn send_multicast(
+        &self,
+        tokens: Vec<String>,
+        message: &PushMessage,
+    ) -> AppResult<MulticastResult> {
+        let results = stream::iter(tokens)
+            .map(|token| async move {
+                let result = self
+                    .send(Message {
+                        token: Some(token.clone()),
+                        ..to_message(message)
+                    })
+                    .await;
+                (token, result)
+            })
+            .buffer_unordered(MULTICAST_CONCURRENCY)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut res = MulticastResult::default();
+        for (token, result) in results {
+            match result {
+                Ok(_) => res.success_count += 1,
+                Err(err) => {
+                    res.failure_count += 1;
+                    if is_unregistered(&err) {
+                        res.unregistered_tokens.push(token);
+                    } else {
+                        tracing::warn!("failed to send push notification: {}", err);
+                    }
+                }
+            }
+        }
+        Ok(res)
+    }
+
+    async fn send_to_topic(&self, topic: &str, message: &PushMessage) -> AppResult<()> {
+        self.send(Message {
+            topic: Some(topic.to_string()),
+            ..to_message(message)
+        })
+        .await
+        .map_err(Internal.from_srcf())
+    }
+}
+
+fn to_message(message: &PushMessage) -> Message {
+    Message {
+        notification: Some(Notification {
+            title: Some(message.title.clone()),
+            body: Some(message.body.clone()),
+            image: message.image_url.clone(),
+        }),
+        data: (!message.data.is_empty()).then(|| message.data.clone()),
+        ..Default::default()
+    }
+}
+
+// アンインストールや期限切れのトークンは NOT_FOUND / UNREGISTERED が返る
+fn is_unregistered(err: &google_fcm1::Error) -> bool {
+    let google_fcm1::Error::BadRequest(value) = err else {
+        return false;
+    };
+    let error = &value["error"];
+    error["status"] == "NOT_FOUND"
+        || error["details"]
+            .as_array()
+            .map(|details| details.iter().any(|v| v["errorCode"] == "UNREGISTERED"))
+            .unwrap_or(false)
+}
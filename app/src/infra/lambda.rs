@@ -6,39 +6,86 @@ use crate::errors::Kind::Internal;
 use crate::infra::lambda::types::ErrorResponse;
 use async_trait::async_trait;
 use aws_sdk_lambda::Client;
+use aws_sdk_lambda::operation::invoke::InvokeOutput;
 use aws_sdk_lambda::primitives::Blob;
+use aws_sdk_lambda::types::InvocationType;
+use std::time::Duration;
 
 #[derive(Clone, Debug)]
 pub struct Adapter {
     client: Client,
+    timeout: Duration,
 }
 
 impl Adapter {
-    pub fn new(client: Client) -> Self {
-        Self { client }
+    pub fn new(client: Client, timeout: Duration) -> Self {
+        Self { client, timeout }
     }
-}
 
-#[async_trait]
-impl RemoteFunction for Adapter {
-    async fn invoke(&self, input: serde_json::Value, arn: &str) -> AppResult<serde_json::Value> {
-        let json = serde_json::to_string(&input).map_err(Internal.from_srcf())?;
-        let resp = self
+    // スロットリングはSDKの標準のリトライで再送されるため、ここではリトライしない
+    // timeout は応答を待つのをやめるだけで、RequestResponse の場合は関数の実行は止まらない
+    async fn send(
+        &self,
+        input: &serde_json::Value,
+        arn: &str,
+        invocation_type: InvocationType,
+    ) -> AppResult<InvokeOutput> {
+        let json = serde_json::to_string(input).map_err(Internal.from_srcf())?;
+        let request = self
             .client
             .invoke()
             .function_name(arn)
+            .invocation_type(invocation_type)
             .payload(Blob::new(json.into_bytes()))
-            .send()
+            .send();
+        tokio::time::timeout(self.timeout, request)
             .await
-            .map_err(Internal.from_srcf())?;
-        let payload = resp.payload.unwrap();
-        let payload = String::from_utf8(payload.into_inner()).ok().unwrap();
+            .map_err(|_| {
+                Internal.with(format!(
+                    "invocation of {} timed out after {:?}",
+                    arn, self.timeout
+                ))
+            })?
+            .map_err(Internal.from_srcf())
+    }
+}
 
-        if let Some(error) = serde_json::from_str::<ErrorResponse>(&payload).ok() {
-            return Err(Internal.with(error.error_message));
+#[async_trait]
+impl RemoteFunction for Adapter {
+    async fn invoke_json(
+        &self,
+        input: serde_json::Value,
+        arn: &str,
+    ) -> AppResult<serde_json::Value> {
+        let resp = self
+            .send(&input, arn, InvocationType::RequestResponse)
+            .await?;
+        let payload = resp
+            .payload
+            .ok_or_else(|| Internal.with(format!("no payload returned from {}", arn)))?;
+        let payload = String::from_utf8(payload.into_inner()).map_err(Internal.from_srcf())?;
+
+        // 関数内で発生したエラーは FunctionError が設定され、ペイロードにエラー内容が入る
+        if let Some(function_error) = resp.function_error {
+            let message = match serde_json::from_str::<ErrorResponse>(&payload) {
+                Ok(error) => match error.error_type {
+                    Some(error_type) => format!("{}: {}", error_type, error.error_message),
+                    None => error.error_message,
+                },
+                Err(_) => payload,
+            };
+            return Err(Internal.with(format!(
+                "function {} failed ({}): {}",
+                arn, function_error, message
+            )));
         }
         let output = serde_json::from_str(&payload).map_err(Internal.from_srcf())?;
 
         Ok(output)
     }
+
+    async fn invoke_event(&self, input: serde_json::Value, arn: &str) -> AppResult<()> {
+        self.send(&input, arn, InvocationType::Event).await?;
+        Ok(())
+    }
 }
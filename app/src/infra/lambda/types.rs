@@ -4,4 +4,6 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "camelCase")]
 pub struct ErrorResponse {
     pub error_message: String,
+    #[serde(default)]
+    pub error_type: Option<String>,
 }
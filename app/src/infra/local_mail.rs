@@ -0,0 +1,89 @@
+use crate::AppResult;
+use crate::adapter::{CapturedMail, Mail, MailOutbox};
+use crate::domain::types::email::Email;
+use crate::domain::types::outgoing_mail::OutgoingMail;
+use crate::domain::types::time::now;
+use crate::errors::Kind::{BadRequest, Internal};
+use crate::infra::mime;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+// 送信せずに .eml として保存する。プロセス内にも送信履歴を保持する
+pub struct Adapter {
+    dir: PathBuf,
+    from: String,
+    outbox: RwLock<Vec<CapturedMail>>,
+}
+
+impl Adapter {
+    pub fn new(dir: PathBuf, from: &str) -> Self {
+        Self {
+            dir,
+            from: from.to_string(),
+            outbox: RwLock::new(vec![]),
+        }
+    }
+}
+
+#[async_trait]
+impl Mail for Adapter {
+    async fn send(&self, mail: OutgoingMail) -> AppResult<()> {
+        mail.validate().map_err(BadRequest.withf())?;
+
+        let sent_at = now();
+        let id = format!(
+            "{}-{}",
+            sent_at.format("%Y%m%d%H%M%S"),
+            hex::encode(rand::random::<[u8; 4]>())
+        );
+        let path = self.dir.join(format!("{}.eml", id));
+        tokio::fs::create_dir_all(&self.dir)
+            .await
+            .map_err(Internal.from_srcf())?;
+        tokio::fs::write(&path, mime::build(&self.from, &mail))
+            .await
+            .map_err(Internal.from_srcf())?;
+
+        let to_strings = |v: &[Email]| v.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let captured = CapturedMail {
+            id,
+            to: to_strings(&mail.to),
+            cc: to_strings(&mail.cc),
+            bcc: to_strings(&mail.bcc),
+            subject: mail.subject,
+            text: mail.text,
+            html: mail.html,
+            attachments: mail.attachments.into_iter().map(|v| v.filename).collect(),
+            path: path.display().to_string(),
+            sent_at: sent_at.to_rfc3339(),
+        };
+        tracing::info!(
+            "mail captured: to={:?} subject={} path={}",
+            captured.to,
+            captured.subject,
+            captured.path
+        );
+        self.outbox.write().unwrap().push(captured);
+        Ok(())
+    }
+}
+
+impl MailOutbox for Adapter {
+    fn list(&self) -> Vec<CapturedMail> {
+        self.outbox.read().unwrap().iter().rev().cloned().collect()
+    }
+
+    fn get(&self, id: &str) -> Option<CapturedMail> {
+        self.outbox
+            .read()
+            .unwrap()
+            .iter()
+            .find(|v| v.id == id)
+            .cloned()
+    }
+
+    fn clear(&self) {
+        self.outbox.write().unwrap().clear();
+    }
+}
@@ -0,0 +1,195 @@
+use crate::adapter::{BatchEntry, BatchPublishResult, TaskQueue};
+use crate::domain::processed_task::Consumer;
+use crate::domain::types::task::{AsyncTaskMessage, TaskAttributes};
+use crate::errors::AppError;
+use crate::errors::Kind::{BadRequest, Internal};
+use crate::infra::rdb::session_manager::SessionManager;
+use crate::{App, AppResult};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel};
+
+// cfn/sqs.yaml の maxReceiveCount と合わせる
+const MAX_RECEIVE_COUNT: u32 = 3;
+// 失敗したメッセージを再配信するまでの時間。ローカルでは待たずに確認できるよう短くする
+const VISIBILITY_TIMEOUT: Duration = Duration::from_secs(10);
+
+// APIのリクエストとDBの接続を取り合わないよう、ローカルのタスク実行には別の接続プールを使う
+static CONSUMER_APP: OnceCell<App> = OnceCell::const_new();
+
+#[derive(Debug)]
+struct Delivery {
+    input: serde_json::Value,
+    attributes: Option<TaskAttributes>,
+    receive_count: u32,
+}
+
+// SNS/SQSの代わりにAPIプロセス内で async_sns_fn / async_sqs_fn と同じ処理を行う
+// リトライの有無もそれぞれのLambdaの設定に合わせる
+#[derive(Clone, Debug)]
+pub struct Adapter {
+    sender: UnboundedSender<Delivery>,
+}
+
+impl Adapter {
+    pub fn new(consumer: Consumer) -> Self {
+        let (sender, receiver) = unbounded_channel();
+        tokio::spawn(consume(consumer, sender.clone(), receiver));
+        Self { sender }
+    }
+
+    fn send(
+        &self,
+        input: serde_json::Value,
+        attributes: Option<TaskAttributes>,
+        delay: Duration,
+    ) -> AppResult<()> {
+        let delivery = Delivery {
+            input,
+            attributes,
+            receive_count: 0,
+        };
+        if delay.is_zero() {
+            return self
+                .sender
+                .send(delivery)
+                .map_err(|_| Internal.with("local task queue is closed"));
+        }
+
+        let sender = self.sender.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            let _ = sender.send(delivery);
+        });
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TaskQueue for Adapter {
+    async fn publish(&self, input: serde_json::Value, _target: &str) -> AppResult<()> {
+        self.send(input, None, Duration::ZERO)
+    }
+
+    async fn publish_with_attributes(
+        &self,
+        input: serde_json::Value,
+        _target: &str,
+        attributes: &TaskAttributes,
+    ) -> AppResult<()> {
+        self.send(input, Some(attributes.clone()), Duration::ZERO)
+    }
+
+    async fn publish_delayed(
+        &self,
+        input: serde_json::Value,
+        _target: &str,
+        delay: Duration,
+    ) -> AppResult<()> {
+        self.send(input, None, delay)
+    }
+
+    async fn publish_fifo(
+        &self,
+        input: serde_json::Value,
+        _target: &str,
+        _group_id: &str,
+        _deduplication_id: &str,
+    ) -> AppResult<()> {
+        self.send(input, None, Duration::ZERO)
+    }
+
+    async fn publish_batch(
+        &self,
+        entries: Vec<BatchEntry>,
+        _target: &str,
+    ) -> AppResult<BatchPublishResult> {
+        let success_count = entries.len();
+        for entry in entries {
+            self.send(entry.input, entry.attributes, Duration::ZERO)?;
+        }
+        Ok(BatchPublishResult {
+            success_count,
+            failures: vec![],
+        })
+    }
+}
+
+// ローカルはDBの接続数が少ないため、1件ずつ順に処理する
+async fn consume(
+    consumer: Consumer,
+    sender: UnboundedSender<Delivery>,
+    mut receiver: UnboundedReceiver<Delivery>,
+) {
+    while let Some(delivery) = receiver.recv().await {
+        process(consumer, &sender, delivery).await;
+    }
+}
+
+async fn process(consumer: Consumer, sender: &UnboundedSender<Delivery>, delivery: Delivery) {
+    let delivery = Delivery {
+        receive_count: delivery.receive_count + 1,
+        ..delivery
+    };
+    let Err(err) = handle(consumer, &delivery).await else {
+        return;
+    };
+
+    // async_sns_fn は MaximumRetryAttempts: 0 のためリトライしない
+    if consumer == Consumer::AsyncSns {
+        tracing::error!("local sns task failed: {:?}, input={}", err, delivery.input);
+        return;
+    }
+    if delivery.receive_count >= MAX_RECEIVE_COUNT {
+        tracing::error!(
+            "local task moved to dead-letter after {} attempts: {:?}, input={}",
+            delivery.receive_count,
+            err,
+            delivery.input
+        );
+        return;
+    }
+    tracing::warn!(
+        "local task failed (attempt {}), retry after {:?}: {:?}",
+        delivery.receive_count,
+        VISIBILITY_TIMEOUT,
+        err
+    );
+    // 待っている間も他のメッセージは処理する
+    let sender = sender.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(VISIBILITY_TIMEOUT).await;
+        let _ = sender.send(delivery);
+    });
+}
+
+async fn handle(consumer: Consumer, delivery: &Delivery) -> AppResult<()> {
+    let app = consumer_app().await?;
+    match consumer {
+        Consumer::AsyncSns => {
+            let message = serde_json::to_string(&delivery.input).map_err(Internal.from_srcf())?;
+            crate::worker::handle_sns_message(app, &message, delivery.attributes.clone()).await
+        }
+        Consumer::AsyncSqs => {
+            let message: AsyncTaskMessage =
+                serde_json::from_value(delivery.input.clone()).map_err(BadRequest.from_srcf())?;
+            crate::worker::handle_message(app, consumer, message).await
+        }
+    }
+}
+
+async fn consumer_app() -> AppResult<&'static App> {
+    CONSUMER_APP
+        .get_or_try_init(|| async {
+            // App の初期化が終わってから届くため、ここでは初期化済みのものが返る
+            let app = crate::app().await?;
+            let db_session = SessionManager::new(&app.env.database_url).await?;
+            Ok::<_, AppError>(App {
+                db_session: Arc::new(db_session),
+                ..app.clone()
+            })
+        })
+        .await
+}
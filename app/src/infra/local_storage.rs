@@ -0,0 +1,49 @@
+pub mod fs;
+pub mod memory;
+pub mod signer;
+pub mod types;
+
+use crate::AppResult;
+use crate::adapter::Storage;
+use crate::domain::types::asset_key::AssetKey;
+use crate::errors::Kind::BadRequest;
+use crate::infra::s3::types::ByteRange;
+
+// S3のListObjectsV2に合わせる
+const PAGE_SIZE: usize = 1000;
+
+// list_objects を使って prefix 配下のキーをすべて取得する
+async fn list_all_keys(storage: &impl Storage, prefix: &str) -> AppResult<Vec<AssetKey>> {
+    let mut keys = vec![];
+    let mut continuation_token: Option<String> = None;
+    loop {
+        let page = storage
+            .list_objects(prefix, continuation_token.take())
+            .await?;
+        keys.extend(page.objects.into_iter().map(|v| v.key));
+        match page.next_token {
+            Some(v) => continuation_token = Some(v),
+            None => break,
+        }
+    }
+    Ok(keys)
+}
+
+// 返す範囲 [start, end) とContent-Rangeを求める
+fn resolve_range(range: Option<ByteRange>, total: u64) -> AppResult<(u64, u64, Option<String>)> {
+    let Some(range) = range else {
+        return Ok((0, total, None));
+    };
+    let (start, end) = range
+        .resolve(total)
+        .ok_or_else(|| BadRequest.with("指定された範囲が不正です"))?;
+    Ok((
+        start,
+        end,
+        Some(format!("bytes {}-{}/{}", start, end - 1, total)),
+    ))
+}
+
+fn new_upload_id() -> String {
+    hex::encode(rand::random::<[u8; 16]>())
+}
@@ -0,0 +1,372 @@
+use crate::AppResult;
+use crate::adapter::Storage;
+use crate::domain::types::asset_key::AssetKey;
+use crate::domain::types::time::LocalDateTime;
+use crate::domain::types::upload_policy::UploadPolicy;
+use crate::errors::Kind::*;
+use crate::infra::local_storage::signer::Signer;
+use crate::infra::local_storage::types::SignedOperation;
+use crate::infra::local_storage::{PAGE_SIZE, list_all_keys, new_upload_id, resolve_range};
+use crate::infra::s3::types::{
+    ByteRange, CompletedPart, HeadObjectResponse, MultipartUpload, ObjectPage, ObjectStream,
+    ObjectSummary, PresignedPost,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::StreamExt;
+use futures_util::stream;
+use http::Uri;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::ErrorKind;
+use std::io::SeekFrom;
+use std::path::{Component, Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+const OBJECTS_DIR: &str = "objects";
+const META_DIR: &str = "meta";
+const MULTIPART_DIR: &str = "multipart";
+const READ_CHUNK_SIZE: u64 = 64 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Meta {
+    content_type: String,
+}
+
+// root/objects にオブジェクト本体、root/meta にContent-Typeなどのメタデータを保存する
+pub struct Adapter {
+    root: PathBuf,
+    signer: Signer,
+}
+
+impl Adapter {
+    pub fn new(root: PathBuf, signer: Signer) -> Self {
+        Self { root, signer }
+    }
+
+    fn object_path(&self, key: &AssetKey) -> AppResult<PathBuf> {
+        Ok(self.root.join(OBJECTS_DIR).join(Self::relative_path(key)?))
+    }
+
+    fn meta_path(&self, key: &AssetKey) -> AppResult<PathBuf> {
+        Ok(self
+            .root
+            .join(META_DIR)
+            .join(format!("{}.json", Self::relative_path(key)?.display())))
+    }
+
+    fn upload_dir(&self, upload: &MultipartUpload) -> AppResult<PathBuf> {
+        if !upload.upload_id.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(BadRequest.with("不正なアップロードIDです"));
+        }
+        Ok(self.root.join(MULTIPART_DIR).join(&upload.upload_id))
+    }
+
+    // キーに .. などが含まれていてもroot外を参照しないようにする
+    fn relative_path(key: &AssetKey) -> AppResult<PathBuf> {
+        let path = PathBuf::from(key.to_string());
+        if path
+            .components()
+            .any(|v| !matches!(v, Component::Normal(_)))
+        {
+            return Err(BadRequest.with("不正なキーです"));
+        }
+        Ok(path)
+    }
+
+    async fn read_meta(&self, key: &AssetKey) -> AppResult<Meta> {
+        match tokio::fs::read(self.meta_path(key)?).await {
+            Ok(v) => serde_json::from_slice(&v).map_err(Internal.from_srcf()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Meta {
+                content_type: "application/octet-stream".to_string(),
+            }),
+            Err(e) => Err(Internal.from_src(e)),
+        }
+    }
+
+    // 本体を読まずにサイズと更新日時から求める。内容が変われば更新日時も変わる
+    fn e_tag(metadata: &std::fs::Metadata) -> String {
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|v| v.duration_since(std::time::UNIX_EPOCH).ok())
+            .unwrap_or_default();
+        format!("{:x}-{:x}", metadata.len(), modified.as_nanos())
+    }
+
+    async fn write(path: &Path, body: &[u8]) -> AppResult<()> {
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(Internal.from_srcf())?;
+        }
+        tokio::fs::write(path, body)
+            .await
+            .map_err(Internal.from_srcf())
+    }
+
+    async fn remove(path: &Path) -> AppResult<bool> {
+        match tokio::fs::remove_file(path).await {
+            Ok(_) => Ok(true),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(Internal.from_src(e)),
+        }
+    }
+
+    // objects 配下のファイルをキー順に列挙する
+    async fn walk(&self) -> AppResult<Vec<(String, PathBuf)>> {
+        let base = self.root.join(OBJECTS_DIR);
+        let mut files = vec![];
+        let mut dirs = vec![base.clone()];
+        while let Some(dir) = dirs.pop() {
+            let mut entries = match tokio::fs::read_dir(&dir).await {
+                Ok(v) => v,
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(Internal.from_src(e)),
+            };
+            while let Some(entry) = entries.next_entry().await.map_err(Internal.from_srcf())? {
+                let path = entry.path();
+                if entry
+                    .file_type()
+                    .await
+                    .map_err(Internal.from_srcf())?
+                    .is_dir()
+                {
+                    dirs.push(path);
+                } else if let Ok(relative) = path.strip_prefix(&base) {
+                    let key = relative
+                        .components()
+                        .map(|v| v.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/");
+                    files.push((key, path));
+                }
+            }
+        }
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(files)
+    }
+}
+
+#[async_trait]
+impl Storage for Adapter {
+    async fn presign_post_for_upload(
+        &self,
+        key: &AssetKey,
+        policy: &UploadPolicy,
+    ) -> AppResult<PresignedPost> {
+        Ok(self.signer.presign_post(key, policy))
+    }
+
+    async fn presign_for_get(&self, key: &AssetKey) -> AppResult<Uri> {
+        self.signer.presign(SignedOperation::Get, key)
+    }
+
+    async fn download_object(&self, key: &AssetKey) -> AppResult<Bytes> {
+        match tokio::fs::read(self.object_path(key)?).await {
+            Ok(v) => Ok(Bytes::from(v)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(NotFound.default()),
+            Err(e) => Err(Internal.from_src(e)),
+        }
+    }
+
+    async fn get_object_stream(
+        &self,
+        key: &AssetKey,
+        range: Option<ByteRange>,
+    ) -> AppResult<ObjectStream> {
+        let mut file = match tokio::fs::File::open(self.object_path(key)?).await {
+            Ok(v) => v,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Err(NotFound.into_err()),
+            Err(e) => return Err(Internal.from_src(e)),
+        };
+        let total = file.metadata().await.map_err(Internal.from_srcf())?.len();
+        let (start, end, content_range) = resolve_range(range, total)?;
+        file.seek(SeekFrom::Start(start))
+            .await
+            .map_err(Internal.from_srcf())?;
+        let meta = self.read_meta(key).await?;
+
+        let body = stream::unfold((file, end - start), |(mut file, remaining)| async move {
+            if remaining == 0 {
+                return None;
+            }
+            let mut buf = vec![0; remaining.min(READ_CHUNK_SIZE) as usize];
+            match file.read(&mut buf).await {
+                Ok(0) => None,
+                Ok(n) => {
+                    buf.truncate(n);
+                    Some((Ok(Bytes::from(buf)), (file, remaining - n as u64)))
+                }
+                Err(e) => Some((Err(Internal.from_src(e)), (file, 0))),
+            }
+        })
+        .boxed();
+
+        Ok(ObjectStream {
+            content_length: end - start,
+            content_type: Some(meta.content_type),
+            content_range,
+            body,
+        })
+    }
+
+    async fn put_object(&self, key: &AssetKey, body: Bytes, content_type: &str) -> AppResult<()> {
+        let meta = serde_json::to_vec(&Meta {
+            content_type: content_type.to_string(),
+        })
+        .map_err(Internal.from_srcf())?;
+        Self::write(&self.object_path(key)?, &body).await?;
+        Self::write(&self.meta_path(key)?, &meta).await
+    }
+
+    async fn create_multipart_upload(
+        &self,
+        key: &AssetKey,
+        content_type: &str,
+    ) -> AppResult<MultipartUpload> {
+        Self::relative_path(key)?;
+        let upload = MultipartUpload {
+            key: key.clone(),
+            upload_id: new_upload_id(),
+            content_type: content_type.to_string(),
+        };
+        tokio::fs::create_dir_all(self.upload_dir(&upload)?)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(upload)
+    }
+
+    async fn upload_part(
+        &self,
+        upload: &MultipartUpload,
+        part_number: i32,
+        body: Bytes,
+    ) -> AppResult<CompletedPart> {
+        let path = self.upload_dir(upload)?.join(part_number.to_string());
+        Self::write(&path, &body).await?;
+        Ok(CompletedPart {
+            part_number,
+            e_tag: hex::encode(Sha256::digest(&body)),
+        })
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        upload: &MultipartUpload,
+        parts: Vec<CompletedPart>,
+    ) -> AppResult<()> {
+        let dir = self.upload_dir(upload)?;
+        let mut body = vec![];
+        for part in parts {
+            let mut data = tokio::fs::read(dir.join(part.part_number.to_string()))
+                .await
+                .map_err(|_| BadRequest.with(format!("part {} is missing", part.part_number)))?;
+            body.append(&mut data);
+        }
+        self.put_object(&upload.key, Bytes::from(body), &upload.content_type)
+            .await?;
+        self.abort_multipart_upload(upload).await
+    }
+
+    async fn abort_multipart_upload(&self, upload: &MultipartUpload) -> AppResult<()> {
+        match tokio::fs::remove_dir_all(self.upload_dir(upload)?).await {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(Internal.from_src(e)),
+        }
+    }
+
+    async fn head_object(&self, key: &AssetKey) -> AppResult<HeadObjectResponse> {
+        let path = self.object_path(key)?;
+        let metadata = match tokio::fs::metadata(&path).await {
+            Ok(v) => v,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Err(NotFound.into_err()),
+            Err(e) => return Err(Internal.from_src(e)),
+        };
+        let meta = self.read_meta(key).await?;
+
+        Ok(HeadObjectResponse {
+            s3_path: format!("file://{}", path.display()),
+            content_length: metadata.len(),
+            content_type: Some(meta.content_type),
+            e_tag: Some(Self::e_tag(&metadata)),
+            last_modified: metadata.modified().ok().map(LocalDateTime::from),
+        })
+    }
+
+    async fn copy_object(&self, src_key: &AssetKey, dest_key: &AssetKey) -> AppResult<()> {
+        let body = self
+            .download_object(src_key)
+            .await
+            .map_err(|_| BadRequest.default())?;
+        let meta = self.read_meta(src_key).await?;
+        self.put_object(dest_key, body, &meta.content_type).await
+    }
+
+    async fn delete_object(&self, key: &AssetKey) -> AppResult<()> {
+        Self::remove(&self.object_path(key)?).await?;
+        Self::remove(&self.meta_path(key)?).await?;
+        Ok(())
+    }
+
+    async fn list_objects(
+        &self,
+        prefix: &str,
+        continuation_token: Option<String>,
+    ) -> AppResult<ObjectPage> {
+        let files = self
+            .walk()
+            .await?
+            .into_iter()
+            .filter(|(k, _)| k.starts_with(prefix))
+            .filter(|(k, _)| match &continuation_token {
+                Some(v) => k > v,
+                None => true,
+            })
+            .take(PAGE_SIZE + 1)
+            .collect::<Vec<_>>();
+        let has_next = files.len() > PAGE_SIZE;
+
+        let mut objects = vec![];
+        for (key, path) in files.into_iter().take(PAGE_SIZE) {
+            let metadata = tokio::fs::metadata(&path)
+                .await
+                .map_err(Internal.from_srcf())?;
+            objects.push(ObjectSummary {
+                key: key.try_into().map_err(Internal.withf())?,
+                size: metadata.len(),
+                last_modified: metadata.modified().ok().map(LocalDateTime::from),
+            });
+        }
+        let next_token = if has_next {
+            objects.last().map(|v| v.key.to_string())
+        } else {
+            None
+        };
+        Ok(ObjectPage {
+            objects,
+            next_token,
+        })
+    }
+
+    async fn list_object_keys(&self, prefix: &str) -> AppResult<Vec<AssetKey>> {
+        list_all_keys(self, prefix).await
+    }
+
+    async fn delete_objects(&self, keys: &[AssetKey]) -> AppResult<u64> {
+        let mut deleted = 0;
+        for key in keys {
+            if Self::remove(&self.object_path(key)?).await? {
+                deleted += 1;
+            }
+            Self::remove(&self.meta_path(key)?).await?;
+        }
+        Ok(deleted)
+    }
+
+    async fn delete_objects_by_prefix(&self, prefix: &str) -> AppResult<u64> {
+        let keys = list_all_keys(self, prefix).await?;
+        self.delete_objects(&keys).await
+    }
+}
@@ -0,0 +1,410 @@
+use crate::AppResult;
+use crate::adapter::Storage;
+use crate::domain::types::asset_key::AssetKey;
+use crate::domain::types::time::{LocalDateTime, now};
+use crate::domain::types::upload_policy::UploadPolicy;
+use crate::errors::Kind::*;
+use crate::infra::local_storage::signer::Signer;
+use crate::infra::local_storage::types::SignedOperation;
+use crate::infra::local_storage::{PAGE_SIZE, list_all_keys, new_upload_id, resolve_range};
+use crate::infra::s3::types::{
+    ByteRange, CompletedPart, HeadObjectResponse, MultipartUpload, ObjectPage, ObjectStream,
+    ObjectSummary, PresignedPost,
+};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::StreamExt;
+use futures_util::stream;
+use http::Uri;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Bound;
+use std::sync::RwLock;
+
+#[derive(Debug, Clone)]
+struct Object {
+    body: Bytes,
+    content_type: String,
+    last_modified: LocalDateTime,
+}
+
+// プロセス内にオブジェクトを保持する。再起動で消えるのでテストや一時的な動作確認向け
+pub struct Adapter {
+    objects: RwLock<BTreeMap<String, Object>>,
+    uploads: RwLock<HashMap<String, BTreeMap<i32, Bytes>>>,
+    signer: Signer,
+}
+
+impl Adapter {
+    pub fn new(signer: Signer) -> Self {
+        Self {
+            objects: RwLock::new(BTreeMap::new()),
+            uploads: RwLock::new(HashMap::new()),
+            signer,
+        }
+    }
+
+    fn get(&self, key: &AssetKey) -> AppResult<Object> {
+        self.objects
+            .read()
+            .unwrap()
+            .get(&key.to_string())
+            .cloned()
+            .ok_or_else(|| NotFound.default())
+    }
+}
+
+#[async_trait]
+impl Storage for Adapter {
+    async fn presign_post_for_upload(
+        &self,
+        key: &AssetKey,
+        policy: &UploadPolicy,
+    ) -> AppResult<PresignedPost> {
+        Ok(self.signer.presign_post(key, policy))
+    }
+
+    async fn presign_for_get(&self, key: &AssetKey) -> AppResult<Uri> {
+        self.signer.presign(SignedOperation::Get, key)
+    }
+
+    async fn download_object(&self, key: &AssetKey) -> AppResult<Bytes> {
+        Ok(self.get(key)?.body)
+    }
+
+    async fn get_object_stream(
+        &self,
+        key: &AssetKey,
+        range: Option<ByteRange>,
+    ) -> AppResult<ObjectStream> {
+        let object = self.get(key)?;
+        let total = object.body.len() as u64;
+        let (start, end, content_range) = resolve_range(range, total)?;
+        let body = object.body.slice(start as usize..end as usize);
+
+        Ok(ObjectStream {
+            content_length: body.len() as u64,
+            content_type: Some(object.content_type),
+            content_range,
+            body: stream::once(async move { Ok(body) }).boxed(),
+        })
+    }
+
+    async fn put_object(&self, key: &AssetKey, body: Bytes, content_type: &str) -> AppResult<()> {
+        self.objects.write().unwrap().insert(
+            key.to_string(),
+            Object {
+                body,
+                content_type: content_type.to_string(),
+                last_modified: now(),
+            },
+        );
+        Ok(())
+    }
+
+    async fn create_multipart_upload(
+        &self,
+        key: &AssetKey,
+        content_type: &str,
+    ) -> AppResult<MultipartUpload> {
+        let upload_id = new_upload_id();
+        self.uploads
+            .write()
+            .unwrap()
+            .insert(upload_id.clone(), BTreeMap::new());
+        Ok(MultipartUpload {
+            key: key.clone(),
+            upload_id,
+            content_type: content_type.to_string(),
+        })
+    }
+
+    async fn upload_part(
+        &self,
+        upload: &MultipartUpload,
+        part_number: i32,
+        body: Bytes,
+    ) -> AppResult<CompletedPart> {
+        let e_tag = hex::encode(Sha256::digest(&body));
+        self.uploads
+            .write()
+            .unwrap()
+            .get_mut(&upload.upload_id)
+            .ok_or_else(|| NotFound.default())?
+            .insert(part_number, body);
+        Ok(CompletedPart { part_number, e_tag })
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        upload: &MultipartUpload,
+        parts: Vec<CompletedPart>,
+    ) -> AppResult<()> {
+        let stored = self
+            .uploads
+            .write()
+            .unwrap()
+            .remove(&upload.upload_id)
+            .ok_or_else(|| NotFound.default())?;
+        let mut body = vec![];
+        for part in parts {
+            let data = stored
+                .get(&part.part_number)
+                .ok_or_else(|| BadRequest.with(format!("part {} is missing", part.part_number)))?;
+            body.extend_from_slice(data);
+        }
+        self.put_object(&upload.key, Bytes::from(body), &upload.content_type)
+            .await
+    }
+
+    async fn abort_multipart_upload(&self, upload: &MultipartUpload) -> AppResult<()> {
+        self.uploads.write().unwrap().remove(&upload.upload_id);
+        Ok(())
+    }
+
+    async fn head_object(&self, key: &AssetKey) -> AppResult<HeadObjectResponse> {
+        let object = self.get(key)?;
+        Ok(HeadObjectResponse {
+            s3_path: format!("memory://{}", key),
+            content_length: object.body.len() as u64,
+            content_type: Some(object.content_type),
+            e_tag: Some(hex::encode(Sha256::digest(&object.body))),
+            last_modified: Some(object.last_modified),
+        })
+    }
+
+    async fn copy_object(&self, src_key: &AssetKey, dest_key: &AssetKey) -> AppResult<()> {
+        let object = self.get(src_key).map_err(|_| BadRequest.default())?;
+        self.objects.write().unwrap().insert(
+            dest_key.to_string(),
+            Object {
+                last_modified: now(),
+                ..object
+            },
+        );
+        Ok(())
+    }
+
+    async fn delete_object(&self, key: &AssetKey) -> AppResult<()> {
+        self.objects.write().unwrap().remove(&key.to_string());
+        Ok(())
+    }
+
+    async fn list_objects(
+        &self,
+        prefix: &str,
+        continuation_token: Option<String>,
+    ) -> AppResult<ObjectPage> {
+        let objects = self.objects.read().unwrap();
+        let start = match continuation_token {
+            Some(v) => Bound::Excluded(v),
+            None => Bound::Included(prefix.to_string()),
+        };
+        let mut page = objects
+            .range((start, Bound::Unbounded))
+            .take_while(|(k, _)| k.starts_with(prefix))
+            .take(PAGE_SIZE + 1)
+            .map(|(k, v)| {
+                Ok(ObjectSummary {
+                    key: k.clone().try_into().map_err(Internal.withf())?,
+                    size: v.body.len() as u64,
+                    last_modified: Some(v.last_modified),
+                })
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+
+        let next_token = if page.len() > PAGE_SIZE {
+            page.truncate(PAGE_SIZE);
+            page.last().map(|v| v.key.to_string())
+        } else {
+            None
+        };
+        Ok(ObjectPage {
+            objects: page,
+            next_token,
+        })
+    }
+
+    async fn list_object_keys(&self, prefix: &str) -> AppResult<Vec<AssetKey>> {
+        list_all_keys(self, prefix).await
+    }
+
+    async fn delete_objects(&self, keys: &[AssetKey]) -> AppResult<u64> {
+        let mut objects = self.objects.write().unwrap();
+        Ok(keys
+            .iter()
+            .filter(|v| objects.remove(&v.to_string()).is_some())
+            .count() as u64)
+    }
+
+    async fn delete_objects_by_prefix(&self, prefix: &str) -> AppResult<u64> {
+        let keys = list_all_keys(self, prefix).await?;
+        self.delete_objects(&keys).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn adapter() -> Adapter {
+        Adapter::new(Signer::new(
+            "http://localhost".to_string(),
+            "secret".to_string(),
+        ))
+    }
+
+    fn key(v: &str) -> AssetKey {
+        v.to_string().try_into().unwrap()
+    }
+
+    #[tokio::test]
+    async fn put_and_head() {
+        let storage = adapter();
+        storage
+            .put_object(&key("a/1"), Bytes::from_static(b"hello"), "text/plain")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            storage.download_object(&key("a/1")).await.unwrap(),
+            Bytes::from_static(b"hello")
+        );
+        let head = storage.head_object(&key("a/1")).await.unwrap();
+        assert_eq!(head.content_length, 5);
+        assert_eq!(head.content_type.as_deref(), Some("text/plain"));
+        assert_eq!(head.e_tag, Some(hex::encode(Sha256::digest(b"hello"))));
+
+        let err = storage.head_object(&key("a/2")).await.err().unwrap();
+        assert_eq!(err.kind, NotFound);
+    }
+
+    #[tokio::test]
+    async fn stream_range() {
+        let storage = adapter();
+        storage
+            .put_object(&key("a/1"), Bytes::from_static(b"0123456789"), "text/plain")
+            .await
+            .unwrap();
+
+        let mut stream = storage
+            .get_object_stream(&key("a/1"), Some(ByteRange::new(2, Some(5))))
+            .await
+            .unwrap();
+        assert_eq!(stream.content_length, 4);
+        assert_eq!(stream.content_range.as_deref(), Some("bytes 2-5/10"));
+        assert_eq!(
+            stream.body.next().await.unwrap().unwrap(),
+            Bytes::from_static(b"2345")
+        );
+
+        let err = storage
+            .get_object_stream(&key("a/1"), Some(ByteRange::new(10, None)))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.kind, BadRequest);
+    }
+
+    #[tokio::test]
+    async fn copy_and_delete() {
+        let storage = adapter();
+        storage
+            .put_object(&key("a/1"), Bytes::from_static(b"hello"), "text/plain")
+            .await
+            .unwrap();
+        storage.copy_object(&key("a/1"), &key("b/1")).await.unwrap();
+        storage.delete_object(&key("a/1")).await.unwrap();
+
+        let head = storage.head_object(&key("b/1")).await.unwrap();
+        assert_eq!(head.content_type.as_deref(), Some("text/plain"));
+        assert!(storage.download_object(&key("a/1")).await.is_err());
+
+        let err = storage
+            .copy_object(&key("a/1"), &key("c/1"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind, BadRequest);
+    }
+
+    #[tokio::test]
+    async fn list_pages_within_prefix() {
+        let storage = adapter();
+        for i in 0..PAGE_SIZE + 1 {
+            storage
+                .put_object(&key(&format!("a/{:05}", i)), Bytes::new(), "text/plain")
+                .await
+                .unwrap();
+        }
+        storage
+            .put_object(&key("b/1"), Bytes::new(), "text/plain")
+            .await
+            .unwrap();
+
+        let first = storage.list_objects("a/", None).await.unwrap();
+        assert_eq!(first.objects.len(), PAGE_SIZE);
+        assert_eq!(first.next_token.as_deref(), Some("a/00999"));
+
+        let second = storage.list_objects("a/", first.next_token).await.unwrap();
+        assert_eq!(second.objects.len(), 1);
+        assert_eq!(second.objects[0].key.to_string(), "a/01000");
+        assert_eq!(second.next_token, None);
+
+        assert_eq!(
+            storage.delete_objects_by_prefix("a/").await.unwrap(),
+            PAGE_SIZE as u64 + 1
+        );
+        assert_eq!(
+            storage.list_object_keys("").await.unwrap(),
+            vec![key("b/1")]
+        );
+    }
+
+    #[tokio::test]
+    async fn multipart_joins_parts_in_given_order() {
+        let storage = adapter();
+        let upload = storage
+            .create_multipart_upload(&key("a/1"), "text/plain")
+            .await
+            .unwrap();
+        let second = storage
+            .upload_part(&upload, 2, Bytes::from_static(b"world"))
+            .await
+            .unwrap();
+        let first = storage
+            .upload_part(&upload, 1, Bytes::from_static(b"hello "))
+            .await
+            .unwrap();
+        storage
+            .complete_multipart_upload(&upload, vec![first, second])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            storage.download_object(&key("a/1")).await.unwrap(),
+            Bytes::from_static(b"hello world")
+        );
+        assert!(storage.uploads.read().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn multipart_abort_discards_parts() {
+        let storage = adapter();
+        let upload = storage
+            .create_multipart_upload(&key("a/1"), "text/plain")
+            .await
+            .unwrap();
+        let part = storage
+            .upload_part(&upload, 1, Bytes::from_static(b"hello"))
+            .await
+            .unwrap();
+        storage.abort_multipart_upload(&upload).await.unwrap();
+
+        let err = storage
+            .complete_multipart_upload(&upload, vec![part])
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind, NotFound);
+        assert!(storage.download_object(&key("a/1")).await.is_err());
+    }
+}
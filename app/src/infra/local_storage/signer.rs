@@ -0,0 +1,105 @@
+use crate::AppResult;
+use crate::adapter::UrlSigner;
+use crate::domain::types::asset_key::AssetKey;
+use crate::domain::types::time::now;
+use crate::domain::types::upload_policy::UploadPolicy;
+use crate::errors::Kind::*;
+use crate::infra::local_storage::types::{SignedOperation, SignedRequest};
+use crate::infra::s3::types::PresignedPost;
+use chrono::Duration;
+use hmac::{Hmac, Mac};
+use http::Uri;
+use sha2::Sha256;
+
+const EXPIRES_IN_SECS: i64 = 60 * 60;
+
+// S3の署名付きURLの代わりに、APIの /api/storage で検証するHMAC署名を発行する
+#[derive(Clone)]
+pub struct Signer {
+    base_url: String,
+    secret: String,
+}
+
+impl Signer {
+    pub fn new(base_url: String, secret: String) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            secret,
+        }
+    }
+
+    pub fn presign(&self, operation: SignedOperation, key: &AssetKey) -> AppResult<Uri> {
+        let request = SignedRequest {
+            operation,
+            key: key.clone(),
+            expires: (now() + Duration::seconds(EXPIRES_IN_SECS)).timestamp(),
+        };
+        format!(
+            "{}/api/storage/{}?expires={}&signature={}",
+            self.base_url,
+            key,
+            request.expires,
+            self.sign(&request)
+        )
+        .parse()
+        .map_err(Internal.from_srcf())
+    }
+
+    pub fn presign_post(&self, key: &AssetKey, policy: &UploadPolicy) -> PresignedPost {
+        let expires_at = now() + Duration::seconds(policy.expires_in_secs as i64);
+        let request = SignedRequest {
+            operation: SignedOperation::Post {
+                content_type: policy.content_type.clone(),
+                min_size: policy.min_size,
+                max_size: policy.max_size,
+            },
+            key: key.clone(),
+            expires: expires_at.timestamp(),
+        };
+        PresignedPost {
+            url: format!("{}/api/storage", self.base_url),
+            fields: vec![
+                ("key".to_string(), key.to_string()),
+                ("Content-Type".to_string(), policy.content_type.clone()),
+                ("x-min-size".to_string(), policy.min_size.to_string()),
+                ("x-max-size".to_string(), policy.max_size.to_string()),
+                ("x-expires".to_string(), request.expires.to_string()),
+                ("x-signature".to_string(), self.sign(&request)),
+            ],
+            expires_at,
+        }
+    }
+
+    fn sign(&self, request: &SignedRequest) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(Self::string_to_sign(request).as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn string_to_sign(request: &SignedRequest) -> String {
+        let operation = match &request.operation {
+            SignedOperation::Get => "GET".to_string(),
+            SignedOperation::Post {
+                content_type,
+                min_size,
+                max_size,
+            } => format!("POST\n{}\n{}\n{}", content_type, min_size, max_size),
+        };
+        format!("{}\n{}\n{}", operation, request.key, request.expires)
+    }
+}
+
+impl UrlSigner for Signer {
+    fn verify(&self, request: &SignedRequest, signature: &str) -> AppResult<()> {
+        if request.expires < now().timestamp() {
+            return Err(Forbidden.with("署名付きURLの有効期限が切れています"));
+        }
+        let signature = hex::decode(signature).map_err(|_| Forbidden.default())?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(Self::string_to_sign(request).as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| Forbidden.default())
+    }
+}
@@ -0,0 +1,19 @@
+use crate::domain::types::asset_key::AssetKey;
+
+// ローカル用Storageが発行するURLで許可する操作
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SignedOperation {
+    Get,
+    Post {
+        content_type: String,
+        min_size: u64,
+        max_size: u64,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct SignedRequest {
+    pub operation: SignedOperation,
+    pub key: AssetKey,
+    pub expires: i64,
+}
@@ -0,0 +1,47 @@
+use crate::AppResult;
+use crate::adapter::{DBSession, Mail};
+use crate::domain::email_suppression::EmailSuppressionRepository;
+use crate::domain::types::outgoing_mail::OutgoingMail;
+use crate::errors::Kind::Suppressed;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+// 送信停止リストに登録された宛先が含まれる場合は送信せずにエラーを返す
+pub struct Adapter {
+    inner: Arc<dyn Mail>,
+    db_session: Arc<dyn DBSession>,
+    repository: Arc<dyn EmailSuppressionRepository>,
+}
+
+impl Adapter {
+    pub fn new(
+        inner: Arc<dyn Mail>,
+        db_session: Arc<dyn DBSession>,
+        repository: Arc<dyn EmailSuppressionRepository>,
+    ) -> Self {
+        Self {
+            inner,
+            db_session,
+            repository,
+        }
+    }
+}
+
+#[async_trait]
+impl Mail for Adapter {
+    async fn send(&self, mail: OutgoingMail) -> AppResult<()> {
+        let suppressions = self
+            .repository
+            .find_by_emails(self.db_session.conn(), mail.recipients().collect())
+            .await?;
+        if !suppressions.is_empty() {
+            let emails = suppressions
+                .iter()
+                .map(|v| format!("{}({})", v.email, v.reason))
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(Suppressed.with(format!("送信停止中のメールアドレスです: {}", emails)));
+        }
+        self.inner.send(mail).await
+    }
+}
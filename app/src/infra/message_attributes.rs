@@ -0,0 +1,25 @@
+use crate::AppResult;
+use crate::domain::types::task::TaskAttributes;
+use crate::errors::Kind::Internal;
+use std::collections::HashMap;
+
+// 属性の値はすべて String 型で送る
+const DATA_TYPE: &str = "String";
+
+// SNSとSQSの MessageAttributeValue は別の型なので、値の組み立てだけを呼び出し側で行う
+pub fn build<V, E>(
+    attributes: &TaskAttributes,
+    build_value: impl Fn(&'static str, String) -> Result<V, E>,
+) -> AppResult<HashMap<String, V>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    attributes
+        .to_pairs()
+        .into_iter()
+        .map(|(name, value)| {
+            let value = build_value(DATA_TYPE, value).map_err(Internal.from_srcf())?;
+            Ok((name.to_string(), value))
+        })
+        .collect()
+}
@@ -0,0 +1,202 @@
+use crate::domain::types::email::Email;
+use crate::domain::types::outgoing_mail::{Attachment, OutgoingMail};
+use crate::domain::types::time::now;
+use base64::prelude::*;
+
+const LINE_LENGTH: usize = 76;
+// エンコードドワードは75文字以内(RFC 2047)。"=?UTF-8?B?" と "?=" を除くと base64 で60文字 = 45バイト
+const ENCODED_WORD_BYTES: usize = 45;
+// RFC 2231 の継続パラメーター1つあたりのエンコード後の長さ
+const PARAM_SECTION_LENGTH: usize = 60;
+
+// SESのRawメッセージやローカルの.emlとして使うMIMEメッセージを組み立てる
+// Bccはヘッダーに含めず、送信時の宛先としてのみ扱う
+pub fn build(from: &str, mail: &OutgoingMail) -> Vec<u8> {
+    let mut out = String::new();
+    push_header(&mut out, "From", from);
+    push_address_header(&mut out, "To", &mail.to);
+    push_address_header(&mut out, "Cc", &mail.cc);
+    push_address_header(&mut out, "Reply-To", &mail.reply_to);
+    push_header(&mut out, "Subject", &encode_word(&mail.subject));
+    push_header(&mut out, "Date", &now().to_rfc2822());
+    push_header(
+        &mut out,
+        "Message-ID",
+        &format!("<{}@{}>", boundary(), domain_of(from)),
+    );
+    for (name, value) in &mail.headers {
+        push_header(&mut out, name, value);
+    }
+    push_header(&mut out, "MIME-Version", "1.0");
+
+    if mail.attachments.is_empty() {
+        push_body(&mut out, mail);
+    } else {
+        let mixed = boundary();
+        push_header(
+            &mut out,
+            "Content-Type",
+            &format!("multipart/mixed; boundary=\"{}\"", mixed),
+        );
+        out.push_str("\r\n");
+        out.push_str(&format!("--{}\r\n", mixed));
+        push_body(&mut out, mail);
+        for attachment in &mail.attachments {
+            out.push_str(&format!("\r\n--{}\r\n", mixed));
+            push_attachment(&mut out, attachment);
+        }
+        out.push_str(&format!("\r\n--{}--\r\n", mixed));
+    }
+    out.into_bytes()
+}
+
+fn push_body(out: &mut String, mail: &OutgoingMail) {
+    match &mail.html {
+        None => push_text_part(out, "text/plain", &mail.text),
+        Some(html) => {
+            let alternative = boundary();
+            push_header(
+                out,
+                "Content-Type",
+                &format!("multipart/alternative; boundary=\"{}\"", alternative),
+            );
+            out.push_str("\r\n");
+            out.push_str(&format!("--{}\r\n", alternative));
+            push_text_part(out, "text/plain", &mail.text);
+            out.push_str(&format!("\r\n--{}\r\n", alternative));
+            push_text_part(out, "text/html", html);
+            out.push_str(&format!("\r\n--{}--\r\n", alternative));
+        }
+    }
+}
+
+fn push_text_part(out: &mut String, content_type: &str, body: &str) {
+    push_header(
+        out,
+        "Content-Type",
+        &format!("{}; charset=UTF-8", content_type),
+    );
+    push_header(out, "Content-Transfer-Encoding", "base64");
+    out.push_str("\r\n");
+    out.push_str(&encode_base64_lines(body.as_bytes()));
+}
+
+fn push_attachment(out: &mut String, attachment: &Attachment) {
+    push_header(
+        out,
+        "Content-Type",
+        &format!(
+            "{};{}",
+            attachment.content_type,
+            encode_param("name", &attachment.filename)
+        ),
+    );
+    push_header(out, "Content-Transfer-Encoding", "base64");
+    push_header(
+        out,
+        "Content-Disposition",
+        &format!(
+            "attachment;{}",
+            encode_param("filename", &attachment.filename)
+        ),
+    );
+    out.push_str("\r\n");
+    out.push_str(&encode_base64_lines(&attachment.data));
+}
+
+fn push_header(out: &mut String, name: &str, value: &str) {
+    out.push_str(&format!("{}: {}\r\n", name, value));
+}
+
+fn push_address_header(out: &mut String, name: &str, emails: &[Email]) {
+    if emails.is_empty() {
+        return;
+    }
+    let value = emails
+        .iter()
+        .map(|v| v.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    push_header(out, name, &value);
+}
+
+// 非ASCIIを含む場合はRFC 2047のエンコードドワードにする
+// 長い値は文字の途中で切らないように複数のワードに分け、折り返して並べる
+fn encode_word(value: &str) -> String {
+    if value.is_ascii() {
+        return value.to_string();
+    }
+    split_at_char_boundary(value, ENCODED_WORD_BYTES)
+        .into_iter()
+        .map(|v| format!("=?UTF-8?B?{}?=", BASE64_STANDARD.encode(v.as_bytes())))
+        .collect::<Vec<_>>()
+        .join("\r\n ")
+}
+
+// ASCIIのみなら引用符で囲み、それ以外はRFC 2231の継続パラメーターにする
+fn encode_param(name: &str, value: &str) -> String {
+    if value.is_ascii() {
+        let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+        return format!(" {}=\"{}\"", name, escaped);
+    }
+    let encoded = value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect::<Vec<_>>();
+    let mut sections = vec![];
+    let mut section = "UTF-8''".to_string();
+    for v in encoded {
+        if section.len() + v.len() > PARAM_SECTION_LENGTH {
+            sections.push(std::mem::take(&mut section));
+        }
+        section.push_str(&v);
+    }
+    sections.push(section);
+    sections
+        .iter()
+        .enumerate()
+        .map(|(i, v)| format!("\r\n {}*{}*={}", name, i, v))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+fn split_at_char_boundary(value: &str, max_bytes: usize) -> Vec<&str> {
+    let mut chunks = vec![];
+    let mut start = 0;
+    let mut end = 0;
+    for (i, c) in value.char_indices() {
+        if i + c.len_utf8() - start > max_bytes {
+            chunks.push(&value[start..end]);
+            start = end;
+        }
+        end = i + c.len_utf8();
+    }
+    chunks.push(&value[start..]);
+    chunks
+}
+
+fn encode_base64_lines(data: &[u8]) -> String {
+    let encoded = BASE64_STANDARD.encode(data);
+    let mut out = String::with_capacity(encoded.len() + encoded.len() / LINE_LENGTH * 2 + 2);
+    for chunk in encoded.as_bytes().chunks(LINE_LENGTH) {
+        out.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+        out.push_str("\r\n");
+    }
+    out
+}
+
+fn boundary() -> String {
+    hex::encode(rand::random::<[u8; 16]>())
+}
+
+fn domain_of(address: &str) -> &str {
+    address
+        .rsplit_once('@')
+        .map(|(_, v)| v.trim_end_matches('>'))
+        .unwrap_or("localhost")
+}
@@ -1,7 +1,17 @@
 #![allow(unused)]
+pub mod api_key;
+pub mod asset;
+pub mod device_token;
+pub mod email_suppression;
+pub mod notification;
+pub mod notification_preference;
 pub mod order;
 pub mod order_detail;
+pub mod processed_task;
+pub mod scheduled_task;
 pub mod user;
+pub mod user_data_export;
+pub mod user_deletion_request;
 
 use crate::AppResult;
 use crate::adapter::DbConn;
@@ -0,0 +1,83 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::api_key::{ApiKey, ApiKeyRepository, Id, Scope};
+use crate::errors::Kind::{Internal, NotFound};
+use crate::infra::rdb::generated::api_keys;
+use crate::infra::rdb::generated::prelude::*;
+use crate::infra::rdb::repository;
+use async_trait::async_trait;
+use sea_orm::{QueryFilter, entity::prelude::*};
+
+impl TryFrom<api_keys::Model> for ApiKey {
+    type Error = String;
+    fn try_from(v: api_keys::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: v.id.into(),
+            name: v.name.try_into()?,
+            key_prefix: v.key_prefix,
+            key_hash: v.key_hash,
+            scopes: Scope::parse_list(&v.scopes)?,
+            expires_at: v.expires_at.map(|v| v.into()),
+            revoked_at: v.revoked_at.map(|v| v.into()),
+            last_used_at: v.last_used_at.map(|v| v.into()),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        })
+    }
+}
+
+impl From<ApiKey> for api_keys::Model {
+    fn from(v: ApiKey) -> Self {
+        Self {
+            id: v.id.into(),
+            name: v.name.into(),
+            key_prefix: v.key_prefix,
+            key_hash: v.key_hash,
+            scopes: Scope::join(&v.scopes),
+            expires_at: v.expires_at.map(|v| v.into()),
+            revoked_at: v.revoked_at.map(|v| v.into()),
+            last_used_at: v.last_used_at.map(|v| v.into()),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Repository;
+
+impl Repository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for Repository {
+    async fn find(&self, db: DbConn<'_>) -> AppResult<Vec<ApiKey>> {
+        repository::find_all::<ApiKeys, ApiKey, _>(db, api_keys::Column::CreatedAt).await
+    }
+
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<ApiKey> {
+        repository::get::<ApiKeys, ApiKey>(db, id).await
+    }
+
+    async fn get_by_hash(&self, db: DbConn<'_>, key_hash: &str) -> AppResult<ApiKey> {
+        ApiKeys::find()
+            .filter(api_keys::Column::KeyHash.eq(key_hash))
+            .one(&db)
+            .await
+            .map_err(Internal.from_srcf())?
+            .ok_or_else(|| NotFound.default())?
+            .try_into()
+            .map_err(Internal.withf())
+    }
+
+    async fn insert(&self, db: DbConn<'_>, api_key: ApiKey) -> AppResult<()> {
+        repository::insert::<ApiKeys, ApiKey>(db, api_key).await
+    }
+
+    async fn update(&self, db: DbConn<'_>, api_key: ApiKey) -> AppResult<()> {
+        repository::update::<ApiKeys, ApiKey, _>(db, api_keys::Column::Id, api_key).await
+    }
+}
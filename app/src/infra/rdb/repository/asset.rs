@@ -0,0 +1,119 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::asset::{Asset, AssetRepository, Id, Status};
+use crate::domain::types::time::LocalDateTime;
+use crate::domain::user;
+use crate::errors::Kind::Internal;
+use crate::infra::rdb::generated::assets;
+use crate::infra::rdb::generated::prelude::*;
+use crate::infra::rdb::repository;
+use async_trait::async_trait;
+use sea_orm::{QueryFilter, QueryOrder, entity::prelude::*};
+
+impl TryFrom<assets::Model> for Asset {
+    type Error = String;
+    fn try_from(v: assets::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: v.id.into(),
+            user_id: v.user_id.into(),
+            key: v.key.try_into()?,
+            upload_key: v.upload_key.try_into()?,
+            status: v.status.try_into()?,
+            content_type: v.content_type,
+            size: v.size.map(|v| v as u64),
+            checksum: v.checksum,
+            width: v.width.map(|v| v as u32),
+            height: v.height.map(|v| v as u32),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        })
+    }
+}
+
+impl From<Asset> for assets::Model {
+    fn from(v: Asset) -> Self {
+        Self {
+            id: v.id.into(),
+            user_id: v.user_id.into(),
+            key: v.key.into(),
+            upload_key: v.upload_key.into(),
+            status: v.status.into(),
+            content_type: v.content_type,
+            size: v.size.map(|v| v as i64),
+            checksum: v.checksum,
+            width: v.width.map(|v| v as i32),
+            height: v.height.map(|v| v as i32),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Repository;
+
+impl Repository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl AssetRepository for Repository {
+    async fn find_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<Vec<Asset>> {
+        Assets::find()
+            .filter(assets::Column::UserId.eq(user_id.as_str()))
+            .order_by_desc(assets::Column::CreatedAt)
+            .all(&db)
+            .await
+            .map_err(Internal.from_srcf())?
+            .into_iter()
+            .map(|v| v.try_into().map_err(Internal.withf()))
+            .collect()
+    }
+
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<Asset> {
+        repository::get::<Assets, Asset>(db, id).await
+    }
+
+    async fn get_multi(&self, db: DbConn<'_>, ids: Vec<&Id>) -> AppResult<Vec<Asset>> {
+        repository::get_multi::<Assets, Asset, _>(db, assets::Column::Id, ids).await
+    }
+
+    async fn insert(&self, db: DbConn<'_>, asset: Asset) -> AppResult<()> {
+        repository::insert::<Assets, Asset>(db, asset).await
+    }
+
+    async fn update(&self, db: DbConn<'_>, asset: Asset) -> AppResult<()> {
+        repository::update::<Assets, Asset, _>(db, assets::Column::Id, asset).await
+    }
+
+    async fn delete(&self, db: DbConn<'_>, id: &Id) -> AppResult<()> {
+        repository::delete::<Assets>(db, id).await
+    }
+
+    async fn delete_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<()> {
+        Assets::delete_many()
+            .filter(assets::Column::UserId.eq(user_id.as_str()))
+            .exec(&db)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(())
+    }
+
+    async fn find_pending_before(
+        &self,
+        db: DbConn<'_>,
+        before: LocalDateTime,
+    ) -> AppResult<Vec<Asset>> {
+        Assets::find()
+            .filter(assets::Column::Status.eq(Status::Pending.to_string()))
+            .filter(assets::Column::CreatedAt.lt(before))
+            .all(&db)
+            .await
+            .map_err(Internal.from_srcf())?
+            .into_iter()
+            .map(|v| v.try_into().map_err(Internal.withf()))
+            .collect()
+    }
+}
@@ -0,0 +1,115 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::device_token::{DeviceToken, DeviceTokenRepository};
+use crate::domain::user;
+use crate::errors::Kind::Internal;
+use crate::infra::rdb::generated::device_tokens;
+use crate::infra::rdb::generated::prelude::*;
+use crate::infra::rdb::repository;
+use async_trait::async_trait;
+use sea_orm::{QueryFilter, QueryOrder, entity::prelude::*};
+
+impl TryFrom<device_tokens::Model> for DeviceToken {
+    type Error = String;
+    fn try_from(v: device_tokens::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: v.id.into(),
+            user_id: v.user_id.into(),
+            token: v.token,
+            platform: v.platform.try_into()?,
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        })
+    }
+}
+
+impl From<DeviceToken> for device_tokens::Model {
+    fn from(v: DeviceToken) -> Self {
+        Self {
+            id: v.id.into(),
+            user_id: v.user_id.into(),
+            token: v.token,
+            platform: v.platform.into(),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Repository;
+
+impl Repository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl DeviceTokenRepository for Repository {
+    async fn find_by_user(
+        &self,
+        db: DbConn<'_>,
+        user_id: &user::Id,
+    ) -> AppResult<Vec<DeviceToken>> {
+        DeviceTokens::find()
+            .filter(device_tokens::Column::UserId.eq(user_id.as_str()))
+            .order_by_desc(device_tokens::Column::UpdatedAt)
+            .all(&db)
+            .await
+            .map_err(Internal.from_srcf())?
+            .into_iter()
+            .map(|v| v.try_into().map_err(Internal.withf()))
+            .collect()
+    }
+
+    async fn upsert(&self, db: DbConn<'_>, device_token: DeviceToken) -> AppResult<()> {
+        repository::upsert::<DeviceTokens, DeviceToken, _, _, _, _>(
+            db,
+            [device_tokens::Column::Token],
+            [
+                device_tokens::Column::UserId,
+                device_tokens::Column::Platform,
+                device_tokens::Column::UpdatedAt,
+            ],
+            device_token,
+        )
+        .await
+    }
+
+    async fn delete_by_token(
+        &self,
+        db: DbConn<'_>,
+        user_id: &user::Id,
+        token: &str,
+    ) -> AppResult<u64> {
+        let res = DeviceTokens::delete_many()
+            .filter(device_tokens::Column::UserId.eq(user_id.as_str()))
+            .filter(device_tokens::Column::Token.eq(token))
+            .exec(&db)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(res.rows_affected)
+    }
+
+    async fn delete_by_tokens(&self, db: DbConn<'_>, tokens: Vec<String>) -> AppResult<u64> {
+        if tokens.is_empty() {
+            return Ok(0);
+        }
+        let res = DeviceTokens::delete_many()
+            .filter(device_tokens::Column::Token.is_in(tokens))
+            .exec(&db)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(res.rows_affected)
+    }
+
+    async fn delete_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<()> {
+        DeviceTokens::delete_many()
+            .filter(device_tokens::Column::UserId.eq(user_id.as_str()))
+            .exec(&db)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::email_suppression::{EmailSuppression, EmailSuppressionRepository, Id};
+use crate::domain::types::email::Email;
+use crate::errors::Kind::Internal;
+use crate::infra::rdb::generated::email_suppressions;
+use crate::infra::rdb::generated::prelude::*;
+use crate::infra::rdb::repository;
+use async_trait::async_trait;
+use sea_orm::{QueryFilter, entity::prelude::*};
+
+impl TryFrom<email_suppressions::Model> for EmailSuppression {
+    type Error = String;
+    fn try_from(v: email_suppressions::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: v.id.into(),
+            email: v.email.try_into()?,
+            reason: v.reason.try_into()?,
+            detail: v.detail,
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        })
+    }
+}
+
+impl From<EmailSuppression> for email_suppressions::Model {
+    fn from(v: EmailSuppression) -> Self {
+        Self {
+            id: v.id.into(),
+            email: v.email.into(),
+            reason: v.reason.into(),
+            detail: v.detail,
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Repository;
+
+impl Repository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl EmailSuppressionRepository for Repository {
+    async fn find(&self, db: DbConn<'_>) -> AppResult<Vec<EmailSuppression>> {
+        repository::find_all::<EmailSuppressions, EmailSuppression, _>(
+            db,
+            email_suppressions::Column::UpdatedAt,
+        )
+        .await
+    }
+
+    async fn find_by_emails(
+        &self,
+        db: DbConn<'_>,
+        emails: Vec<&Email>,
+    ) -> AppResult<Vec<EmailSuppression>> {
+        if emails.is_empty() {
+            return Ok(vec![]);
+        }
+        EmailSuppressions::find()
+            .filter(
+                email_suppressions::Column::Email
+                    .is_in(emails.into_iter().map(|v| v.to_lowercase().to_string())),
+            )
+            .all(&db)
+            .await
+            .map_err(Internal.from_srcf())?
+            .into_iter()
+            .map(|v| v.try_into().map_err(Internal.withf()))
+            .collect()
+    }
+
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<EmailSuppression> {
+        repository::get::<EmailSuppressions, EmailSuppression>(db, id).await
+    }
+
+    async fn upsert(&self, db: DbConn<'_>, suppression: EmailSuppression) -> AppResult<()> {
+        repository::upsert::<EmailSuppressions, EmailSuppression, _, _, _, _>(
+            db,
+            [email_suppressions::Column::Email],
+            [
+                email_suppressions::Column::Reason,
+                email_suppressions::Column::Detail,
+                email_suppressions::Column::UpdatedAt,
+            ],
+            suppression,
+        )
+        .await
+    }
+
+    async fn delete(&self, db: DbConn<'_>, id: &Id) -> AppResult<()> {
+        repository::delete::<EmailSuppressions>(db, id).await
+    }
+}
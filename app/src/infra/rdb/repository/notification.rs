@@ -0,0 +1,130 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::notification::{Id, Notification, NotificationRepository};
+use crate::domain::types::time::now;
+use crate::domain::user;
+use crate::errors::Kind::Internal;
+use crate::infra::rdb::generated::notifications;
+use crate::infra::rdb::generated::prelude::*;
+use crate::infra::rdb::repository;
+use async_trait::async_trait;
+use sea_orm::sea_query::Expr;
+use sea_orm::{PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, entity::prelude::*};
+
+impl TryFrom<notifications::Model> for Notification {
+    type Error = String;
+    fn try_from(v: notifications::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: v.id.into(),
+            user_id: v.user_id.into(),
+            notification_type: v.r#type.try_into()?,
+            payload: v.payload,
+            read_at: v.read_at.map(|v| v.into()),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        })
+    }
+}
+
+impl From<Notification> for notifications::Model {
+    fn from(v: Notification) -> Self {
+        Self {
+            id: v.id.into(),
+            user_id: v.user_id.into(),
+            r#type: v.notification_type.into(),
+            payload: v.payload,
+            read_at: v.read_at.map(|v| v.into()),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Repository;
+
+impl Repository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl NotificationRepository for Repository {
+    async fn find_by_user(
+        &self,
+        db: DbConn<'_>,
+        user_id: &user::Id,
+        offset: u64,
+        limit: u64,
+    ) -> AppResult<Vec<Notification>> {
+        Notifications::find()
+            .filter(notifications::Column::UserId.eq(user_id.as_str()))
+            .order_by_desc(notifications::Column::CreatedAt)
+            .order_by_desc(notifications::Column::Id)
+            .offset(offset)
+            .limit(limit)
+            .all(&db)
+            .await
+            .map_err(Internal.from_srcf())?
+            .into_iter()
+            .map(|v| v.try_into().map_err(Internal.withf()))
+            .collect()
+    }
+
+    async fn count_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<u64> {
+        Notifications::find()
+            .filter(notifications::Column::UserId.eq(user_id.as_str()))
+            .count(&db)
+            .await
+            .map_err(Internal.from_srcf())
+    }
+
+    async fn count_unread_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<u64> {
+        Notifications::find()
+            .filter(notifications::Column::UserId.eq(user_id.as_str()))
+            .filter(notifications::Column::ReadAt.is_null())
+            .count(&db)
+            .await
+            .map_err(Internal.from_srcf())
+    }
+
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<Notification> {
+        repository::get::<Notifications, Notification>(db, id).await
+    }
+
+    async fn insert(&self, db: DbConn<'_>, notification: Notification) -> AppResult<()> {
+        repository::insert::<Notifications, Notification>(db, notification).await
+    }
+
+    async fn update(&self, db: DbConn<'_>, notification: Notification) -> AppResult<()> {
+        repository::update::<Notifications, Notification, _>(
+            db,
+            notifications::Column::Id,
+            notification,
+        )
+        .await
+    }
+
+    async fn mark_all_read(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<u64> {
+        let read_at = now();
+        let res = Notifications::update_many()
+            .col_expr(notifications::Column::ReadAt, Expr::value(read_at))
+            .col_expr(notifications::Column::UpdatedAt, Expr::value(read_at))
+            .filter(notifications::Column::UserId.eq(user_id.as_str()))
+            .filter(notifications::Column::ReadAt.is_null())
+            .exec(&db)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(res.rows_affected)
+    }
+
+    async fn delete_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<()> {
+        Notifications::delete_many()
+            .filter(notifications::Column::UserId.eq(user_id.as_str()))
+            .exec(&db)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(())
+    }
+}
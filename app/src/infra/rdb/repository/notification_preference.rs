@@ -0,0 +1,95 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::notification::Category;
+use crate::domain::notification::preference::{
+    NotificationPreferenceRepository, Preference, QuietHours,
+};
+use crate::domain::user;
+use crate::errors::Kind::{Internal, NotFound};
+use crate::infra::rdb::generated::notification_preferences;
+use crate::infra::rdb::generated::prelude::*;
+use crate::infra::rdb::repository;
+use async_trait::async_trait;
+use sea_orm::{QueryFilter, entity::prelude::*};
+
+impl TryFrom<notification_preferences::Model> for Preference {
+    type Error = String;
+    fn try_from(v: notification_preferences::Model) -> Result<Self, Self::Error> {
+        let quiet_hours = match (v.quiet_hours_start, v.quiet_hours_end) {
+            (Some(start), Some(end)) => Some(QuietHours::new(start as u32, end as u32)?),
+            _ => None,
+        };
+        Ok(Self {
+            id: v.id.into(),
+            user_id: v.user_id.into(),
+            push_opt_outs: Category::parse_list(&v.push_opt_outs)?,
+            email_opt_outs: Category::parse_list(&v.email_opt_outs)?,
+            quiet_hours,
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        })
+    }
+}
+
+impl From<Preference> for notification_preferences::Model {
+    fn from(v: Preference) -> Self {
+        Self {
+            id: v.id.into(),
+            user_id: v.user_id.into(),
+            push_opt_outs: Category::join(&v.push_opt_outs),
+            email_opt_outs: Category::join(&v.email_opt_outs),
+            quiet_hours_start: v.quiet_hours.map(|v| v.start_hour as i32),
+            quiet_hours_end: v.quiet_hours.map(|v| v.end_hour as i32),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Repository;
+
+impl Repository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl NotificationPreferenceRepository for Repository {
+    async fn get_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<Preference> {
+        NotificationPreferences::find()
+            .filter(notification_preferences::Column::UserId.eq(user_id.as_str()))
+            .one(&db)
+            .await
+            .map_err(Internal.from_srcf())?
+            .ok_or_else(|| NotFound.default())?
+            .try_into()
+            .map_err(Internal.withf())
+    }
+
+    async fn upsert(&self, db: DbConn<'_>, preference: Preference) -> AppResult<()> {
+        repository::upsert::<NotificationPreferences, Preference, _, _, _, _>(
+            db,
+            [notification_preferences::Column::UserId],
+            [
+                notification_preferences::Column::PushOptOuts,
+                notification_preferences::Column::EmailOptOuts,
+                notification_preferences::Column::QuietHoursStart,
+                notification_preferences::Column::QuietHoursEnd,
+                notification_preferences::Column::UpdatedAt,
+            ],
+            preference,
+        )
+        .await
+    }
+
+    async fn delete_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<()> {
+        NotificationPreferences::delete_many()
+            .filter(notification_preferences::Column::UserId.eq(user_id.as_str()))
+            .exec(&db)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(())
+    }
+}
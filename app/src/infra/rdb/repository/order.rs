@@ -7,6 +7,7 @@ use crate::infra::rdb::generated::orders;
 use crate::infra::rdb::generated::prelude::*;
 use crate::infra::rdb::repository;
 use async_trait::async_trait;
+use sea_orm::sea_query::Expr;
 use sea_orm::{QueryFilter, QueryOrder, entity::prelude::*};
 
 impl TryFrom<orders::Model> for Order {
@@ -14,7 +15,7 @@ impl TryFrom<orders::Model> for Order {
     fn try_from(v: orders::Model) -> Result<Self, Self::Error> {
         Ok(Self {
             id: v.id.into(),
-            user_id: v.user_id.into(),
+            user_id: v.user_id.map(|v| v.into()),
             created_at: v.created_at.into(),
             updated_at: v.updated_at.into(),
         })
@@ -25,7 +26,7 @@ impl From<Order> for orders::Model {
     fn from(v: Order) -> Self {
         Self {
             id: v.id.into(),
-            user_id: v.user_id.into(),
+            user_id: v.user_id.map(|v| v.into()),
             created_at: v.created_at.into(),
             updated_at: v.updated_at.into(),
         }
@@ -78,4 +79,14 @@ impl OrderRepository for Repository {
     async fn delete(&self, db: DbConn<'_>, id: &Id) -> AppResult<()> {
         repository::delete::<Orders>(db, id).await
     }
+
+    async fn anonymize_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<()> {
+        Orders::update_many()
+            .col_expr(orders::Column::UserId, Expr::value(Option::<String>::None))
+            .filter(orders::Column::UserId.eq(user_id.as_str()))
+            .exec(&db)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(())
+    }
 }
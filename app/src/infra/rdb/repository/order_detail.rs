@@ -96,4 +96,13 @@ impl OrderDetailRepository for Repository {
     async fn delete(&self, db: DbConn<'_>, id: &Id) -> AppResult<()> {
         repository::delete::<OrderDetails>(db, id).await
     }
+
+    async fn delete_by_order(&self, db: DbConn<'_>, order_id: &order::Id) -> AppResult<()> {
+        OrderDetails::delete_many()
+            .filter(order_details::Column::OrderId.eq(order_id.as_str()))
+            .exec(&db)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(())
+    }
 }
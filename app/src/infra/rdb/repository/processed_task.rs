@@ -0,0 +1,96 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::processed_task::{Id, ProcessedTask, ProcessedTaskRepository, Status};
+use crate::domain::types::time::{LocalDateTime, now};
+use crate::errors::Kind::Internal;
+use crate::infra::rdb::generated::prelude::*;
+use crate::infra::rdb::generated::processed_tasks;
+use crate::infra::rdb::repository;
+use async_trait::async_trait;
+use sea_orm::sea_query::Expr;
+use sea_orm::{QueryFilter, entity::prelude::*};
+
+impl TryFrom<processed_tasks::Model> for ProcessedTask {
+    type Error = String;
+    fn try_from(v: processed_tasks::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: v.id.into(),
+            task_type: v.task_type,
+            status: v.status.try_into()?,
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        })
+    }
+}
+
+impl From<ProcessedTask> for processed_tasks::Model {
+    fn from(v: ProcessedTask) -> Self {
+        Self {
+            id: v.id.into(),
+            task_type: v.task_type,
+            status: v.status.into(),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Repository;
+
+impl Repository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl ProcessedTaskRepository for Repository {
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<ProcessedTask> {
+        repository::get::<ProcessedTasks, ProcessedTask>(db, id).await
+    }
+
+    async fn insert(&self, db: DbConn<'_>, task: ProcessedTask) -> AppResult<()> {
+        repository::insert::<ProcessedTasks, ProcessedTask>(db, task).await
+    }
+
+    async fn update(&self, db: DbConn<'_>, task: ProcessedTask) -> AppResult<()> {
+        repository::update::<ProcessedTasks, ProcessedTask, _>(
+            db,
+            processed_tasks::Column::Id,
+            task,
+        )
+        .await
+    }
+
+    async fn reclaim(
+        &self,
+        db: DbConn<'_>,
+        id: &Id,
+        stale_before: LocalDateTime,
+    ) -> AppResult<bool> {
+        // 条件付きで更新し、同時に取り直そうとしても一方だけが成功するようにする
+        let res = ProcessedTasks::update_many()
+            .col_expr(processed_tasks::Column::UpdatedAt, Expr::value(now()))
+            .filter(processed_tasks::Column::Id.eq(id.as_str()))
+            .filter(processed_tasks::Column::Status.eq(Status::Processing.to_string()))
+            .filter(processed_tasks::Column::UpdatedAt.lt(stale_before))
+            .exec(&db)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(res.rows_affected == 1)
+    }
+
+    async fn delete(&self, db: DbConn<'_>, id: &Id) -> AppResult<()> {
+        repository::delete::<ProcessedTasks>(db, id).await
+    }
+
+    async fn delete_before(&self, db: DbConn<'_>, until: LocalDateTime) -> AppResult<u64> {
+        let res = ProcessedTasks::delete_many()
+            .filter(processed_tasks::Column::CreatedAt.lt(until))
+            .exec(&db)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(res.rows_affected)
+    }
+}
@@ -0,0 +1,106 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::scheduled_task::{Id, ScheduledTask, ScheduledTaskRepository, Status};
+use crate::domain::types::time::LocalDateTime;
+use crate::errors::Kind::Internal;
+use crate::infra::rdb::generated::prelude::*;
+use crate::infra::rdb::generated::scheduled_tasks;
+use crate::infra::rdb::repository;
+use async_trait::async_trait;
+use sea_orm::{QueryFilter, QueryOrder, QuerySelect, entity::prelude::*};
+
+impl TryFrom<scheduled_tasks::Model> for ScheduledTask {
+    type Error = String;
+    fn try_from(v: scheduled_tasks::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: v.id.into(),
+            payload: serde_json::from_value(v.payload).map_err(|e| e.to_string())?,
+            run_at: v.run_at.into(),
+            status: v.status.try_into()?,
+            attempts: v.attempts as u32,
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        })
+    }
+}
+
+impl From<ScheduledTask> for scheduled_tasks::Model {
+    fn from(v: ScheduledTask) -> Self {
+        Self {
+            id: v.id.into(),
+            payload: serde_json::to_value(&v.payload).unwrap_or_default(),
+            run_at: v.run_at.into(),
+            status: v.status.into(),
+            attempts: v.attempts as i32,
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Repository;
+
+impl Repository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl ScheduledTaskRepository for Repository {
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<ScheduledTask> {
+        repository::get::<ScheduledTasks, ScheduledTask>(db, id).await
+    }
+
+    async fn find_due(
+        &self,
+        db: DbConn<'_>,
+        until: LocalDateTime,
+        limit: u64,
+    ) -> AppResult<Vec<ScheduledTask>> {
+        ScheduledTasks::find()
+            .filter(scheduled_tasks::Column::Status.eq(Status::Pending.to_string()))
+            .filter(scheduled_tasks::Column::RunAt.lte(until))
+            .order_by_asc(scheduled_tasks::Column::RunAt)
+            .limit(limit)
+            .all(&db)
+            .await
+            .map_err(Internal.from_srcf())?
+            .into_iter()
+            .map(|v| v.try_into().map_err(Internal.withf()))
+            .collect()
+    }
+
+    async fn find_stale(
+        &self,
+        db: DbConn<'_>,
+        before: LocalDateTime,
+        limit: u64,
+    ) -> AppResult<Vec<ScheduledTask>> {
+        ScheduledTasks::find()
+            .filter(scheduled_tasks::Column::Status.eq(Status::Enqueued.to_string()))
+            .filter(scheduled_tasks::Column::UpdatedAt.lte(before))
+            .order_by_asc(scheduled_tasks::Column::UpdatedAt)
+            .limit(limit)
+            .all(&db)
+            .await
+            .map_err(Internal.from_srcf())?
+            .into_iter()
+            .map(|v| v.try_into().map_err(Internal.withf()))
+            .collect()
+    }
+
+    async fn insert(&self, db: DbConn<'_>, task: ScheduledTask) -> AppResult<()> {
+        repository::insert::<ScheduledTasks, ScheduledTask>(db, task).await
+    }
+
+    async fn update(&self, db: DbConn<'_>, task: ScheduledTask) -> AppResult<()> {
+        repository::update::<ScheduledTasks, ScheduledTask, _>(
+            db,
+            scheduled_tasks::Column::Id,
+            task,
+        )
+        .await
+    }
+}
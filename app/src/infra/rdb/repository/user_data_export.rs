@@ -0,0 +1,87 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::user;
+use crate::domain::user::data_export::{DataExport, DataExportRepository, Id};
+use crate::errors::Kind::Internal;
+use crate::infra::rdb::generated::data_exports;
+use crate::infra::rdb::generated::prelude::*;
+use crate::infra::rdb::repository;
+use async_trait::async_trait;
+use sea_orm::{QueryFilter, QueryOrder, entity::prelude::*};
+
+impl TryFrom<data_exports::Model> for DataExport {
+    type Error = String;
+    fn try_from(v: data_exports::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: v.id.into(),
+            user_id: v.user_id.into(),
+            status: v.status.try_into()?,
+            object_key: v.object_key.map(|v| v.try_into()).transpose()?,
+            last_error: v.last_error,
+            completed_at: v.completed_at.map(|v| v.into()),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        })
+    }
+}
+
+impl From<DataExport> for data_exports::Model {
+    fn from(v: DataExport) -> Self {
+        Self {
+            id: v.id.into(),
+            user_id: v.user_id.into(),
+            status: v.status.into(),
+            object_key: v.object_key.map(|v| v.into()),
+            last_error: v.last_error,
+            completed_at: v.completed_at.map(|v| v.into()),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Repository;
+
+impl Repository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl DataExportRepository for Repository {
+    async fn find_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<Vec<DataExport>> {
+        DataExports::find()
+            .filter(data_exports::Column::UserId.eq(user_id.as_str()))
+            .order_by_desc(data_exports::Column::CreatedAt)
+            .all(&db)
+            .await
+            .map_err(Internal.from_srcf())?
+            .into_iter()
+            .map(|v| v.try_into().map_err(Internal.withf()))
+            .collect()
+    }
+
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<DataExport> {
+        repository::get::<DataExports, DataExport>(db, id).await
+    }
+
+    async fn insert(&self, db: DbConn<'_>, data_export: DataExport) -> AppResult<()> {
+        repository::insert::<DataExports, DataExport>(db, data_export).await
+    }
+
+    async fn update(&self, db: DbConn<'_>, data_export: DataExport) -> AppResult<()> {
+        repository::update::<DataExports, DataExport, _>(db, data_exports::Column::Id, data_export)
+            .await
+    }
+
+    async fn delete_by_user(&self, db: DbConn<'_>, user_id: &user::Id) -> AppResult<()> {
+        DataExports::delete_many()
+            .filter(data_exports::Column::UserId.eq(user_id.as_str()))
+            .exec(&db)
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,96 @@
+use crate::AppResult;
+use crate::adapter::DbConn;
+use crate::domain::user;
+use crate::domain::user::deletion_request::{DeletionRequest, DeletionRequestRepository, Id};
+use crate::errors::Kind::Internal;
+use crate::infra::rdb::generated::deletion_requests;
+use crate::infra::rdb::generated::prelude::*;
+use crate::infra::rdb::repository;
+use async_trait::async_trait;
+use sea_orm::{QueryFilter, QueryOrder, entity::prelude::*};
+
+impl TryFrom<deletion_requests::Model> for DeletionRequest {
+    type Error = String;
+    fn try_from(v: deletion_requests::Model) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: v.id.into(),
+            user_id: v.user_id.into(),
+            status: v.status.try_into()?,
+            step: v.step.try_into()?,
+            attempts: v.attempts as u32,
+            last_error: v.last_error,
+            completed_at: v.completed_at.map(|v| v.into()),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        })
+    }
+}
+
+impl From<DeletionRequest> for deletion_requests::Model {
+    fn from(v: DeletionRequest) -> Self {
+        Self {
+            id: v.id.into(),
+            user_id: v.user_id.into(),
+            status: v.status.into(),
+            step: v.step.into(),
+            attempts: v.attempts as i32,
+            last_error: v.last_error,
+            completed_at: v.completed_at.map(|v| v.into()),
+            created_at: v.created_at.into(),
+            updated_at: v.updated_at.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Repository;
+
+impl Repository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl DeletionRequestRepository for Repository {
+    async fn find(&self, db: DbConn<'_>) -> AppResult<Vec<DeletionRequest>> {
+        repository::find_all::<DeletionRequests, DeletionRequest, _>(
+            db,
+            deletion_requests::Column::CreatedAt,
+        )
+        .await
+    }
+
+    async fn find_by_user(
+        &self,
+        db: DbConn<'_>,
+        user_id: &user::Id,
+    ) -> AppResult<Vec<DeletionRequest>> {
+        DeletionRequests::find()
+            .filter(deletion_requests::Column::UserId.eq(user_id.as_str()))
+            .order_by_desc(deletion_requests::Column::CreatedAt)
+            .all(&db)
+            .await
+            .map_err(Internal.from_srcf())?
+            .into_iter()
+            .map(|v| v.try_into().map_err(Internal.withf()))
+            .collect()
+    }
+
+    async fn get(&self, db: DbConn<'_>, id: &Id) -> AppResult<DeletionRequest> {
+        repository::get::<DeletionRequests, DeletionRequest>(db, id).await
+    }
+
+    async fn insert(&self, db: DbConn<'_>, request: DeletionRequest) -> AppResult<()> {
+        repository::insert::<DeletionRequests, DeletionRequest>(db, request).await
+    }
+
+    async fn update(&self, db: DbConn<'_>, request: DeletionRequest) -> AppResult<()> {
+        repository::update::<DeletionRequests, DeletionRequest, _>(
+            db,
+            deletion_requests::Column::Id,
+            request,
+        )
+        .await
+    }
+}
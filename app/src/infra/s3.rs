@@ -1,29 +1,47 @@
+mod post_policy;
 pub mod types;
 
 use crate::AppResult;
 use crate::adapter::Storage;
 use crate::domain::types::asset_key::AssetKey;
+use crate::domain::types::time::{FromTimestamp, LocalDateTime};
+use crate::domain::types::upload_policy::UploadPolicy;
 use crate::errors::Kind::*;
-use crate::infra::s3::types::HeadObjectResponse;
+use crate::infra::s3::types::{
+    ByteRange, CompletedPart, HeadObjectResponse, MultipartUpload, ObjectPage, ObjectStream,
+    ObjectSummary, PresignedPost,
+};
 use async_trait::async_trait;
+use aws_credential_types::provider::{ProvideCredentials, SharedCredentialsProvider};
 use aws_sdk_s3::Client;
 use aws_sdk_s3::error::SdkError;
 use aws_sdk_s3::operation::get_object::GetObjectError;
 use aws_sdk_s3::presigning::PresigningConfig;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{CompletedMultipartUpload, Delete, ObjectIdentifier};
 use bytes::Bytes;
+use futures_util::StreamExt;
+use futures_util::stream;
 use http::Uri;
 use std::time::Duration;
 
 #[derive(Clone, Debug)]
 pub struct Adapter {
     client: Client,
+    // POSTポリシーの署名に使う。Clientの設定からは取得できない
+    credentials: Option<SharedCredentialsProvider>,
     default_bucket: String,
 }
 
 impl Adapter {
-    pub fn new(client: Client, bucket: String) -> Self {
+    pub fn new(
+        client: Client,
+        credentials: Option<SharedCredentialsProvider>,
+        bucket: String,
+    ) -> Self {
         Self {
             client,
+            credentials,
             default_bucket: bucket,
         }
     }
@@ -31,17 +49,31 @@ impl Adapter {
 
 #[async_trait]
 impl Storage for Adapter {
-    async fn presign_for_upload(&self, key: &AssetKey) -> AppResult<Uri> {
-        let expires_in = Duration::from_secs(60 * 60);
-        let pre_signed = self
-            .client
-            .put_object()
-            .bucket(self.default_bucket.clone())
-            .key(key.to_string())
-            .presigned(PresigningConfig::expires_in(expires_in).unwrap())
+    async fn presign_post_for_upload(
+        &self,
+        key: &AssetKey,
+        policy: &UploadPolicy,
+    ) -> AppResult<PresignedPost> {
+        let credentials = self
+            .credentials
+            .as_ref()
+            .ok_or_else(|| Internal.with("credentials provider is not configured"))?
+            .provide_credentials()
             .await
             .map_err(Internal.from_srcf())?;
-        Ok(pre_signed.uri().parse().map_err(Internal.from_srcf())?)
+        let region = self
+            .client
+            .config()
+            .region()
+            .ok_or_else(|| Internal.with("region is not configured"))?;
+
+        post_policy::sign(
+            &credentials,
+            region.as_ref(),
+            &self.default_bucket,
+            key,
+            policy,
+        )
     }
 
     async fn presign_for_get(&self, key: &AssetKey) -> AppResult<Uri> {
@@ -69,8 +101,154 @@ impl Storage for Adapter {
                 SdkError::ServiceError(v) if v.err().is_no_such_key() => NotFound.default(),
                 _ => Internal.from_src(e),
             })?;
-        let data = resp.body.collect().await;
-        Ok(data.unwrap().into_bytes())
+        let data = resp.body.collect().await.map_err(Internal.from_srcf())?;
+        Ok(data.into_bytes())
+    }
+
+    async fn get_object_stream(
+        &self,
+        key: &AssetKey,
+        range: Option<ByteRange>,
+    ) -> AppResult<ObjectStream> {
+        let resp = self
+            .client
+            .get_object()
+            .bucket(self.default_bucket.clone())
+            .key(key.to_string())
+            .set_range(range.map(|v| v.to_header()))
+            .send()
+            .await
+            .map_err(|e: SdkError<GetObjectError>| match &e {
+                SdkError::ServiceError(v) if v.err().is_no_such_key() => NotFound.default(),
+                SdkError::ServiceError(v) if v.raw().status().as_u16() == 416 => {
+                    BadRequest.with("指定された範囲が不正です")
+                }
+                _ => Internal.from_src(e),
+            })?;
+
+        let content_length = resp.content_length().unwrap_or_default() as u64;
+        let content_type = resp.content_type().map(|v| v.to_string());
+        let content_range = resp.content_range().map(|v| v.to_string());
+        let body = stream::unfold(resp.body, |mut body| async move {
+            body.next()
+                .await
+                .map(|v| (v.map_err(Internal.from_srcf()), body))
+        })
+        .boxed();
+
+        Ok(ObjectStream {
+            content_length,
+            content_type,
+            content_range,
+            body,
+        })
+    }
+
+    async fn put_object(&self, key: &AssetKey, body: Bytes, content_type: &str) -> AppResult<()> {
+        self.client
+            .put_object()
+            .bucket(self.default_bucket.clone())
+            .key(key.to_string())
+            .content_type(content_type)
+            .body(ByteStream::from(body))
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(())
+    }
+
+    async fn create_multipart_upload(
+        &self,
+        key: &AssetKey,
+        content_type: &str,
+    ) -> AppResult<MultipartUpload> {
+        let resp = self
+            .client
+            .create_multipart_upload()
+            .bucket(self.default_bucket.clone())
+            .key(key.to_string())
+            .content_type(content_type)
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+        let upload_id = resp
+            .upload_id()
+            .ok_or_else(|| Internal.with("upload id is missing"))?;
+
+        Ok(MultipartUpload {
+            key: key.clone(),
+            upload_id: upload_id.to_string(),
+            content_type: content_type.to_string(),
+        })
+    }
+
+    async fn upload_part(
+        &self,
+        upload: &MultipartUpload,
+        part_number: i32,
+        body: Bytes,
+    ) -> AppResult<CompletedPart> {
+        let resp = self
+            .client
+            .upload_part()
+            .bucket(self.default_bucket.clone())
+            .key(upload.key.to_string())
+            .upload_id(upload.upload_id.clone())
+            .part_number(part_number)
+            .body(ByteStream::from(body))
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+        let e_tag = resp
+            .e_tag()
+            .ok_or_else(|| Internal.with("etag is missing"))?;
+
+        Ok(CompletedPart {
+            part_number,
+            e_tag: e_tag.to_string(),
+        })
+    }
+
+    async fn complete_multipart_upload(
+        &self,
+        upload: &MultipartUpload,
+        parts: Vec<CompletedPart>,
+    ) -> AppResult<()> {
+        let parts = parts
+            .into_iter()
+            .map(|v| {
+                aws_sdk_s3::types::CompletedPart::builder()
+                    .part_number(v.part_number)
+                    .e_tag(v.e_tag)
+                    .build()
+            })
+            .collect();
+        self.client
+            .complete_multipart_upload()
+            .bucket(self.default_bucket.clone())
+            .key(upload.key.to_string())
+            .upload_id(upload.upload_id.clone())
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(())
+    }
+
+    async fn abort_multipart_upload(&self, upload: &MultipartUpload) -> AppResult<()> {
+        self.client
+            .abort_multipart_upload()
+            .bucket(self.default_bucket.clone())
+            .key(upload.key.to_string())
+            .upload_id(upload.upload_id.clone())
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+        Ok(())
     }
 
     async fn head_object(&self, key: &AssetKey) -> AppResult<HeadObjectResponse> {
@@ -82,16 +260,22 @@ impl Storage for Adapter {
             .send()
             .await;
 
-        match res {
+        let res = match res {
             Err(SdkError::ServiceError(err)) if err.err().is_not_found() => {
                 return Err(NotFound.into_err());
             }
             Err(e) => return Err(Internal.from_src(e)),
-            _ => {}
-        }
+            Ok(v) => v,
+        };
 
         Ok(HeadObjectResponse {
             s3_path: format!("s3://{}/{}", self.default_bucket, key),
+            content_length: res.content_length().unwrap_or_default() as u64,
+            content_type: res.content_type().map(|v| v.to_string()),
+            e_tag: res.e_tag().map(|v| v.trim_matches('"').to_string()),
+            last_modified: res
+                .last_modified()
+                .and_then(|v| LocalDateTime::from_timestamp(v.secs()).ok()),
         })
     }
 
@@ -128,4 +312,113 @@ impl Storage for Adapter {
         }
         Ok(())
     }
+    async fn list_objects(
+        &self,
+        prefix: &str,
+        continuation_token: Option<String>,
+    ) -> AppResult<ObjectPage> {
+        let resp = self
+            .client
+            .list_objects_v2()
+            .bucket(self.default_bucket.clone())
+            .prefix(prefix)
+            .set_continuation_token(continuation_token)
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+
+        let objects = resp
+            .contents()
+            .iter()
+            .filter_map(|v| {
+                v.key().map(|key| {
+                    Ok(ObjectSummary {
+                        key: key.to_string().try_into().map_err(Internal.withf())?,
+                        size: v.size().unwrap_or_default() as u64,
+                        last_modified: v
+                            .last_modified()
+                            .and_then(|t| LocalDateTime::from_timestamp(t.secs()).ok()),
+                    })
+                })
+            })
+            .collect::<AppResult<Vec<_>>>()?;
+        let next_token = match resp.next_continuation_token() {
+            Some(v) if resp.is_truncated().unwrap_or(false) => Some(v.to_string()),
+            _ => None,
+        };
+        Ok(ObjectPage {
+            objects,
+            next_token,
+        })
+    }
+
+    async fn list_object_keys(&self, prefix: &str) -> AppResult<Vec<AssetKey>> {
+        let mut keys = vec![];
+        let mut continuation_token: Option<String> = None;
+        loop {
+            let page = self.list_objects(prefix, continuation_token.take()).await?;
+            keys.extend(page.objects.into_iter().map(|v| v.key));
+            match page.next_token {
+                Some(v) => continuation_token = Some(v),
+                None => break,
+            }
+        }
+        Ok(keys)
+    }
+
+    // DeleteObjectsは1リクエスト1000件まで
+    async fn delete_objects(&self, keys: &[AssetKey]) -> AppResult<u64> {
+        let mut deleted = 0;
+        for chunk in keys.chunks(1000) {
+            let objects = chunk
+                .iter()
+                .map(|v| {
+                    ObjectIdentifier::builder()
+                        .key(v.to_string())
+                        .build()
+                        .map_err(Internal.from_srcf())
+                })
+                .collect::<AppResult<Vec<_>>>()?;
+            let resp = self
+                .client
+                .delete_objects()
+                .bucket(self.default_bucket.clone())
+                .delete(
+                    Delete::builder()
+                        .set_objects(Some(objects))
+                        .quiet(true)
+                        .build()
+                        .map_err(Internal.from_srcf())?,
+                )
+                .send()
+                .await
+                .map_err(Internal.from_srcf())?;
+            for err in resp.errors() {
+                tracing::warn!(
+                    "failed to delete object {:?}: {:?}",
+                    err.key(),
+                    err.message()
+                );
+            }
+            deleted += (chunk.len() - resp.errors().len()) as u64;
+        }
+        Ok(deleted)
+    }
+
+    async fn delete_objects_by_prefix(&self, prefix: &str) -> AppResult<u64> {
+        let mut deleted = 0;
+        let mut continuation_token: Option<String> = None;
+        loop {
+            let page = self.list_objects(prefix, continuation_token.take()).await?;
+            let keys = page.objects.into_iter().map(|v| v.key).collect::<Vec<_>>();
+            if !keys.is_empty() {
+                deleted += self.delete_objects(&keys).await?;
+            }
+            match page.next_token {
+                Some(v) => continuation_token = Some(v),
+                None => break,
+            }
+        }
+        Ok(deleted)
+    }
 }
@@ -0,0 +1,85 @@
+use crate::AppResult;
+use crate::domain::types::asset_key::AssetKey;
+use crate::domain::types::upload_policy::UploadPolicy;
+use crate::errors::Kind::Internal;
+use crate::infra::s3::types::PresignedPost;
+use aws_credential_types::Credentials;
+use base64::prelude::*;
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use serde_json::{Value, json};
+use sha2::Sha256;
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+
+// SDKがPOSTポリシーの署名に対応していないため、SigV4の仕様に沿って自前で署名する
+// https://docs.aws.amazon.com/AmazonS3/latest/API/sigv4-HTTPPOSTConstructPolicy.html
+pub fn sign(
+    credentials: &Credentials,
+    region: &str,
+    bucket: &str,
+    key: &AssetKey,
+    policy: &UploadPolicy,
+) -> AppResult<PresignedPost> {
+    let now = Utc::now();
+    let date = now.format("%Y%m%d").to_string();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let expires_at = now + Duration::seconds(policy.expires_in_secs as i64);
+    let credential = format!(
+        "{}/{}/{}/s3/aws4_request",
+        credentials.access_key_id(),
+        date,
+        region
+    );
+
+    let mut fields = vec![
+        ("key".to_string(), key.to_string()),
+        ("Content-Type".to_string(), policy.content_type.clone()),
+        ("x-amz-algorithm".to_string(), ALGORITHM.to_string()),
+        ("x-amz-credential".to_string(), credential.clone()),
+        ("x-amz-date".to_string(), amz_date.clone()),
+    ];
+    let mut conditions: Vec<Value> = vec![
+        json!({ "bucket": bucket }),
+        json!(["eq", "$key", key.to_string()]),
+        json!(["eq", "$Content-Type", policy.content_type]),
+        json!(["content-length-range", policy.min_size, policy.max_size]),
+        json!({ "x-amz-algorithm": ALGORITHM }),
+        json!({ "x-amz-credential": credential }),
+        json!({ "x-amz-date": amz_date }),
+    ];
+    if let Some(token) = credentials.session_token() {
+        fields.push(("x-amz-security-token".to_string(), token.to_string()));
+        conditions.push(json!({ "x-amz-security-token": token }));
+    }
+
+    let policy_document = json!({
+        "expiration": expires_at.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+        "conditions": conditions,
+    });
+    let encoded_policy =
+        BASE64_STANDARD.encode(serde_json::to_vec(&policy_document).map_err(Internal.from_srcf())?);
+
+    let signing_key = [date.as_str(), region, "s3", "aws4_request"]
+        .iter()
+        .try_fold(
+            format!("AWS4{}", credentials.secret_access_key()).into_bytes(),
+            |key, v| hmac_sha256(&key, v.as_bytes()),
+        )?;
+    let signature = hex::encode(hmac_sha256(&signing_key, encoded_policy.as_bytes())?);
+
+    fields.push(("policy".to_string(), encoded_policy));
+    fields.push(("x-amz-signature".to_string(), signature));
+
+    Ok(PresignedPost {
+        url: format!("https://{}.s3.{}.amazonaws.com/", bucket, region),
+        fields,
+        expires_at: expires_at.into(),
+    })
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> AppResult<Vec<u8>> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).map_err(Internal.from_srcf())?;
+    mac.update(data);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
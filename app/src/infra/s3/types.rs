@@ -1,3 +1,95 @@
+use crate::AppResult;
+use crate::domain::types::asset_key::AssetKey;
+use crate::domain::types::time::LocalDateTime;
+use bytes::Bytes;
+use futures_util::stream::BoxStream;
+
 pub struct HeadObjectResponse {
     pub s3_path: String,
+    pub content_length: u64,
+    pub content_type: Option<String>,
+    pub e_tag: Option<String>,
+    pub last_modified: Option<LocalDateTime>,
+}
+
+pub struct PresignedPost {
+    pub url: String,
+    pub fields: Vec<(String, String)>,
+    pub expires_at: LocalDateTime,
+}
+
+pub struct ObjectSummary {
+    pub key: AssetKey,
+    pub size: u64,
+    pub last_modified: Option<LocalDateTime>,
+}
+
+pub struct ObjectPage {
+    pub objects: Vec<ObjectSummary>,
+    pub next_token: Option<String>,
+}
+
+pub type BodyStream = BoxStream<'static, AppResult<Bytes>>;
+
+pub struct ObjectStream {
+    pub content_length: u64,
+    pub content_type: Option<String>,
+    // Rangeを指定した場合は "bytes start-end/total"
+    pub content_range: Option<String>,
+    pub body: BodyStream,
+}
+
+// HTTPのRangeと同じく end を含む
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: Option<u64>,
+}
+impl ByteRange {
+    pub fn new(start: u64, end: Option<u64>) -> Self {
+        Self { start, end }
+    }
+
+    // "bytes=0-99" / "bytes=100-" の単一範囲のみ対応
+    pub fn parse_header(value: &str) -> Option<Self> {
+        let (start, end) = value.strip_prefix("bytes=")?.split_once('-')?;
+        let start = start.parse().ok()?;
+        let end = match end {
+            "" => None,
+            v => Some(v.parse().ok()?),
+        };
+        if end.map(|v| v < start).unwrap_or(false) {
+            return None;
+        }
+        Some(Self { start, end })
+    }
+
+    pub fn to_header(&self) -> String {
+        match self.end {
+            Some(end) => format!("bytes={}-{}", self.start, end),
+            None => format!("bytes={}-", self.start),
+        }
+    }
+
+    // 実際に返す範囲を [start, end) で返す
+    pub fn resolve(&self, total: u64) -> Option<(u64, u64)> {
+        if self.start >= total {
+            return None;
+        }
+        let end = self.end.map(|v| v + 1).unwrap_or(total).min(total);
+        Some((self.start, end))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MultipartUpload {
+    pub key: AssetKey,
+    pub upload_id: String,
+    pub content_type: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CompletedPart {
+    pub part_number: i32,
+    pub e_tag: String,
 }
@@ -42,9 +42,11 @@ impl ErrorNotifier for Adapter {
 
         let level = match err.kind {
             Kind::Internal => sentry::Level::Error,
-            Kind::BadRequest | Kind::Unauthorized | Kind::Forbidden | Kind::Duplicate => {
-                sentry::Level::Warning
-            }
+            Kind::BadRequest
+            | Kind::Unauthorized
+            | Kind::Forbidden
+            | Kind::Duplicate
+            | Kind::Suppressed => sentry::Level::Warning,
             Kind::NotFound => sentry::Level::Info,
         };
 
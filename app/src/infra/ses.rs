@@ -2,12 +2,15 @@ use async_graphql::async_trait::async_trait;
 use aws_sdk_sesv2::Client;
 use aws_sdk_sesv2::error::SdkError;
 use aws_sdk_sesv2::operation::send_email::SendEmailError;
-use aws_sdk_sesv2::types::{Body, Content, Destination, EmailContent, Message};
+use aws_sdk_sesv2::primitives::Blob;
+use aws_sdk_sesv2::types::{Destination, EmailContent, RawMessage};
 
 use crate::AppResult;
 use crate::adapter::Mail;
 use crate::domain::types::email::Email;
-use crate::errors::Kind::Internal;
+use crate::domain::types::outgoing_mail::OutgoingMail;
+use crate::errors::Kind::{BadRequest, Internal};
+use crate::infra::mime;
 
 #[derive(Clone, Debug)]
 pub struct Adapter {
@@ -26,25 +29,21 @@ impl Adapter {
 
 #[async_trait]
 impl Mail for Adapter {
-    async fn send_text(&self, to: Email, subject: &str, text: &str) -> AppResult<()> {
-        let destination = Destination::builder().to_addresses(to.to_string()).build();
+    // 添付ファイルや任意ヘッダーを扱うため、常にRawメッセージとして送信する
+    async fn send(&self, mail: OutgoingMail) -> AppResult<()> {
+        mail.validate().map_err(BadRequest.withf())?;
 
-        let subject_content = Content::builder()
-            .data(subject)
-            .charset("UTF-8")
-            .build()
-            .map_err(|e| Internal.with(e.to_string()))?;
-        let body_content = Content::builder()
-            .data(text)
-            .charset("UTF-8")
+        let to_strings = |v: &[Email]| v.iter().map(|v| v.to_string()).collect::<Vec<_>>();
+        let destination = Destination::builder()
+            .set_to_addresses(Some(to_strings(&mail.to)))
+            .set_cc_addresses(Some(to_strings(&mail.cc)))
+            .set_bcc_addresses(Some(to_strings(&mail.bcc)))
+            .build();
+        let raw = RawMessage::builder()
+            .data(Blob::new(mime::build(&self.from, &mail)))
             .build()
             .map_err(|e| Internal.with(e.to_string()))?;
-        let body = Body::builder().text(body_content).build();
-        let message = Message::builder()
-            .subject(subject_content)
-            .body(body)
-            .build();
-        let email_content = EmailContent::builder().simple(message).build();
+        let email_content = EmailContent::builder().raw(raw).build();
 
         self.client
             .send_email()
@@ -1,8 +1,14 @@
 use crate::AppResult;
-use crate::adapter::TaskQueue;
+use crate::adapter::{BatchEntry, BatchPublishResult, TaskQueue};
+use crate::domain::types::task::TaskAttributes;
 use crate::errors::Kind::*;
+use crate::infra::batch_publish::{self, EntryFailure, RequestError};
+use crate::infra::message_attributes;
 use async_trait::async_trait;
 use aws_sdk_sns::Client;
+use aws_sdk_sns::types::{MessageAttributeValue, PublishBatchRequestEntry};
+use std::collections::HashMap;
+use std::time::Duration;
 
 #[derive(Clone, Debug)]
 pub struct Adapter {
@@ -15,6 +21,17 @@ impl Adapter {
     }
 }
 
+fn to_sdk_attributes(
+    attributes: &TaskAttributes,
+) -> AppResult<HashMap<String, MessageAttributeValue>> {
+    message_attributes::build(attributes, |data_type, value| {
+        MessageAttributeValue::builder()
+            .data_type(data_type)
+            .string_value(value)
+            .build()
+    })
+}
+
 #[async_trait]
 impl TaskQueue for Adapter {
     async fn publish(&self, input: serde_json::Value, target: &str) -> AppResult<()> {
@@ -29,4 +46,97 @@ impl TaskQueue for Adapter {
 
         Ok(())
     }
+
+    async fn publish_with_attributes(
+        &self,
+        input: serde_json::Value,
+        target: &str,
+        attributes: &TaskAttributes,
+    ) -> AppResult<()> {
+        let json = serde_json::to_string(&input).map_err(Internal.from_srcf())?;
+        self.client
+            .publish()
+            .topic_arn(target)
+            .message(json)
+            .set_message_attributes(Some(to_sdk_attributes(attributes)?))
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+
+        Ok(())
+    }
+
+    async fn publish_delayed(
+        &self,
+        _input: serde_json::Value,
+        _target: &str,
+        _delay: Duration,
+    ) -> AppResult<()> {
+        Err(Internal.with("SNS does not support delayed delivery"))
+    }
+
+    async fn publish_fifo(
+        &self,
+        input: serde_json::Value,
+        target: &str,
+        group_id: &str,
+        deduplication_id: &str,
+    ) -> AppResult<()> {
+        let json = serde_json::to_string(&input).map_err(Internal.from_srcf())?;
+        self.client
+            .publish()
+            .topic_arn(target)
+            .message(json)
+            .message_group_id(group_id)
+            .message_deduplication_id(deduplication_id)
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+
+        Ok(())
+    }
+
+    async fn publish_batch(
+        &self,
+        entries: Vec<BatchEntry>,
+        target: &str,
+    ) -> AppResult<BatchPublishResult> {
+        batch_publish::publish(entries, |chunk| async move {
+            let entries = chunk
+                .into_iter()
+                .map(|v| {
+                    PublishBatchRequestEntry::builder()
+                        .id(v.id)
+                        .message(v.body)
+                        .set_message_group_id(v.group_id)
+                        .set_message_deduplication_id(v.deduplication_id)
+                        .set_message_attributes(
+                            v.attributes.as_ref().map(to_sdk_attributes).transpose()?,
+                        )
+                        .build()
+                        .map_err(Internal.from_srcf())
+                })
+                .collect::<AppResult<Vec<_>>>()?;
+            let res = self
+                .client
+                .publish_batch()
+                .topic_arn(target)
+                .set_publish_batch_request_entries(Some(entries))
+                .send()
+                .await?;
+
+            Ok::<_, RequestError>(
+                res.failed()
+                    .iter()
+                    .map(|v| EntryFailure {
+                        id: v.id().to_string(),
+                        code: v.code().to_string(),
+                        message: v.message().map(|m| m.to_string()),
+                        sender_fault: v.sender_fault(),
+                    })
+                    .collect(),
+            )
+        })
+        .await
+    }
 }
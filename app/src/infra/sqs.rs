@@ -1,8 +1,23 @@
 use crate::AppResult;
-use crate::adapter::TaskQueue;
+use crate::adapter::{
+    BatchEntry, BatchPublishResult, MAX_TASK_DELAY, QueueReader, ReceivedMessage, TaskQueue,
+};
+use crate::domain::types::task::TaskAttributes;
+use crate::domain::types::time::{FromTimestamp, LocalDateTime};
 use crate::errors::Kind::*;
+use crate::infra::batch_publish::{self, EntryFailure, RequestError};
+use crate::infra::message_attributes;
 use async_trait::async_trait;
 use aws_sdk_sqs::Client;
+use aws_sdk_sqs::types::{
+    MessageAttributeValue, MessageSystemAttributeName, SendMessageBatchRequestEntry,
+};
+use std::collections::HashMap;
+use std::time::Duration;
+
+// ショートポーリングは一部のサーバーしか見ないため、メッセージがあっても空で返ることがある
+// ロングポーリングにして、空で返った場合はキューが空であるとみなせるようにする
+const RECEIVE_WAIT_TIME_SECS: i32 = 1;
 
 #[derive(Clone, Debug)]
 pub struct Adapter {
@@ -15,6 +30,17 @@ impl Adapter {
     }
 }
 
+fn to_sdk_attributes(
+    attributes: &TaskAttributes,
+) -> AppResult<HashMap<String, MessageAttributeValue>> {
+    message_attributes::build(attributes, |data_type, value| {
+        MessageAttributeValue::builder()
+            .data_type(data_type)
+            .string_value(value)
+            .build()
+    })
+}
+
 #[async_trait]
 impl TaskQueue for Adapter {
     async fn publish(&self, input: serde_json::Value, target: &str) -> AppResult<()> {
@@ -29,4 +55,185 @@ impl TaskQueue for Adapter {
 
         Ok(())
     }
+
+    async fn publish_with_attributes(
+        &self,
+        input: serde_json::Value,
+        target: &str,
+        attributes: &TaskAttributes,
+    ) -> AppResult<()> {
+        let json = serde_json::to_string(&input).map_err(Internal.from_srcf())?;
+        self.client
+            .send_message()
+            .queue_url(target)
+            .message_body(json)
+            .set_message_attributes(Some(to_sdk_attributes(attributes)?))
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+
+        Ok(())
+    }
+
+    async fn publish_delayed(
+        &self,
+        input: serde_json::Value,
+        target: &str,
+        delay: Duration,
+    ) -> AppResult<()> {
+        if delay > MAX_TASK_DELAY {
+            return Err(BadRequest.with(format!(
+                "delay must be less than or equal to {} seconds",
+                MAX_TASK_DELAY.as_secs()
+            )));
+        }
+        let json = serde_json::to_string(&input).map_err(Internal.from_srcf())?;
+        self.client
+            .send_message()
+            .queue_url(target)
+            .message_body(json)
+            .delay_seconds(delay.as_secs() as i32)
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+
+        Ok(())
+    }
+
+    async fn publish_fifo(
+        &self,
+        input: serde_json::Value,
+        target: &str,
+        group_id: &str,
+        deduplication_id: &str,
+    ) -> AppResult<()> {
+        let json = serde_json::to_string(&input).map_err(Internal.from_srcf())?;
+        self.client
+            .send_message()
+            .queue_url(target)
+            .message_body(json)
+            .message_group_id(group_id)
+            .message_deduplication_id(deduplication_id)
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+
+        Ok(())
+    }
+
+    async fn publish_batch(
+        &self,
+        entries: Vec<BatchEntry>,
+        target: &str,
+    ) -> AppResult<BatchPublishResult> {
+        batch_publish::publish(entries, |chunk| async move {
+            let entries = chunk
+                .into_iter()
+                .map(|v| {
+                    SendMessageBatchRequestEntry::builder()
+                        .id(v.id)
+                        .message_body(v.body)
+                        .set_message_group_id(v.group_id)
+                        .set_message_deduplication_id(v.deduplication_id)
+                        .set_message_attributes(
+                            v.attributes.as_ref().map(to_sdk_attributes).transpose()?,
+                        )
+                        .build()
+                        .map_err(Internal.from_srcf())
+                })
+                .collect::<AppResult<Vec<_>>>()?;
+            let res = self
+                .client
+                .send_message_batch()
+                .queue_url(target)
+                .set_entries(Some(entries))
+                .send()
+                .await?;
+
+            Ok::<_, RequestError>(
+                res.failed()
+                    .iter()
+                    .map(|v| EntryFailure {
+                        id: v.id().to_string(),
+                        code: v.code().to_string(),
+                        message: v.message().map(|m| m.to_string()),
+                        sender_fault: v.sender_fault(),
+                    })
+                    .collect(),
+            )
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl QueueReader for Adapter {
+    async fn receive(
+        &self,
+        target: &str,
+        max_messages: i32,
+        visibility_timeout: Duration,
+    ) -> AppResult<Vec<ReceivedMessage>> {
+        let res = self
+            .client
+            .receive_message()
+            .queue_url(target)
+            .max_number_of_messages(max_messages)
+            .visibility_timeout(visibility_timeout.as_secs() as i32)
+            .wait_time_seconds(RECEIVE_WAIT_TIME_SECS)
+            .message_system_attribute_names(MessageSystemAttributeName::All)
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+
+        Ok(res
+            .messages
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|v| {
+                let attr = |name: MessageSystemAttributeName| {
+                    v.attributes.as_ref().and_then(|a| a.get(&name)).cloned()
+                };
+                let receive_count = attr(MessageSystemAttributeName::ApproximateReceiveCount)
+                    .and_then(|c| c.parse().ok())
+                    .unwrap_or_default();
+                // SentTimestamp はミリ秒
+                let sent_at = attr(MessageSystemAttributeName::SentTimestamp)
+                    .and_then(|t| t.parse::<i64>().ok())
+                    .and_then(|t| LocalDateTime::from_timestamp(t / 1000).ok());
+                Some(ReceivedMessage {
+                    message_id: v.message_id?,
+                    receipt_handle: v.receipt_handle?,
+                    body: v.body.unwrap_or_default(),
+                    receive_count,
+                    sent_at,
+                })
+            })
+            .collect())
+    }
+
+    async fn delete(&self, target: &str, receipt_handle: &str) -> AppResult<()> {
+        self.client
+            .delete_message()
+            .queue_url(target)
+            .receipt_handle(receipt_handle)
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+
+        Ok(())
+    }
+
+    async fn release(&self, target: &str, receipt_handle: &str) -> AppResult<()> {
+        self.client
+            .change_message_visibility()
+            .queue_url(target)
+            .receipt_handle(receipt_handle)
+            .visibility_timeout(0)
+            .send()
+            .await
+            .map_err(Internal.from_srcf())?;
+
+        Ok(())
+    }
 }
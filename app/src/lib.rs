@@ -1,15 +1,30 @@
 use crate::adapter::{
-    AdminAuth, DBSession, ErrorNotifier, ImageCdn, Mail, RemoteFunction, Storage, TaskQueue,
-    UserAuth,
+    AdminAuth, DBSession, ErrorNotifier, ImageCdn, Mail, MailOutbox, PushNotifier, QueueReader,
+    RemoteFunction, Storage, TaskQueue, UrlSigner, UserAuth,
 };
+use crate::domain::api_key::ApiKeyRepository;
+use crate::domain::asset::AssetRepository;
+use crate::domain::device_token::DeviceTokenRepository;
+use crate::domain::email_suppression::EmailSuppressionRepository;
+use crate::domain::notification::NotificationRepository;
+use crate::domain::notification::preference::NotificationPreferenceRepository;
 use crate::domain::order::OrderRepository;
 use crate::domain::order::detail::OrderDetailRepository;
+use crate::domain::processed_task::{Consumer, ProcessedTaskRepository};
+use crate::domain::scheduled_task::ScheduledTaskRepository;
 use crate::domain::user::UserRepository;
+use crate::domain::user::data_export::DataExportRepository;
+use crate::domain::user::deletion_request::DeletionRequestRepository;
+use crate::env::StorageDriver;
 use crate::errors::AppError;
 use crate::errors::Kind::Internal;
+use crate::infra::local_storage::signer::Signer;
 use crate::infra::sentry as sentry_adapter;
-use crate::infra::{cloudfront, cognito, firebase, ssm};
+use crate::infra::{
+    cloudfront, cognito, firebase, local_mail, local_queue, local_storage, mail_suppression, ssm,
+};
 use aws_config::BehaviorVersion;
+use google_fcm1::FirebaseCloudMessaging;
 use google_identitytoolkit3::IdentityToolkit;
 use google_identitytoolkit3::yup_oauth2::ServiceAccountAuthenticator;
 use google_identitytoolkit3::yup_oauth2::client::CustomHyperClientBuilder;
@@ -24,12 +39,18 @@ use std::sync::Arc;
 use tokio::sync::{Mutex, OnceCell};
 
 pub mod adapter;
+pub mod batch;
+pub mod dlq;
 pub mod domain;
 mod env;
 pub mod errors;
 mod infra;
 pub mod jwt;
+pub mod mail_template;
+pub mod notify;
+pub mod push;
 pub mod util;
+pub mod worker;
 
 pub type AppResult<T> = Result<T, AppError>;
 
@@ -37,19 +58,33 @@ pub type AppResult<T> = Result<T, AppError>;
 pub struct App {
     pub env: env::Env,
     pub storage: Arc<dyn Storage>,
+    pub storage_url_signer: Option<Arc<dyn UrlSigner>>,
     pub mail: Arc<dyn Mail>,
+    pub mail_outbox: Option<Arc<dyn MailOutbox>>,
     pub error_notifier: Arc<dyn ErrorNotifier>,
     pub admin_auth: Arc<dyn AdminAuth>,
     pub sns_task_queue: Arc<dyn TaskQueue>,
     pub sqs_task_queue: Arc<dyn TaskQueue>,
+    pub sqs_queue_reader: Arc<dyn QueueReader>,
     pub remote_function: Arc<dyn RemoteFunction>,
     pub db_session: Arc<dyn DBSession>,
     pub user_repository: Arc<dyn UserRepository>,
     pub order_repository: Arc<dyn OrderRepository>,
     pub order_detail_repository: Arc<dyn OrderDetailRepository>,
+    pub api_key_repository: Arc<dyn ApiKeyRepository>,
+    pub user_deletion_request_repository: Arc<dyn DeletionRequestRepository>,
+    pub user_data_export_repository: Arc<dyn DataExportRepository>,
+    pub asset_repository: Arc<dyn AssetRepository>,
+    pub email_suppression_repository: Arc<dyn EmailSuppressionRepository>,
+    pub device_token_repository: Arc<dyn DeviceTokenRepository>,
+    pub notification_repository: Arc<dyn NotificationRepository>,
+    pub notification_preference_repository: Arc<dyn NotificationPreferenceRepository>,
+    pub scheduled_task_repository: Arc<dyn ScheduledTaskRepository>,
+    pub processed_task_repository: Arc<dyn ProcessedTaskRepository>,
 
     pub image_cdn: Option<Arc<dyn ImageCdn>>,
     pub user_auth: Option<Arc<dyn UserAuth>>,
+    pub push_notifier: Option<Arc<dyn PushNotifier>>,
 }
 
 impl std::fmt::Debug for App {
@@ -81,14 +116,49 @@ pub async fn app() -> AppResult<&'static App> {
     ssm.load_dotenv().await?;
     let envs = env::Env::new();
 
-    let storage: Arc<dyn Storage> = Arc::new(s3::Adapter::new(
-        aws_sdk_s3::Client::new(&aws_config),
-        envs.s3_bucket_name.clone(),
-    ));
-    let mail: Arc<dyn Mail> = Arc::new(infra::ses::Adapter::new(
-        aws_sdk_sesv2::Client::new(&aws_config),
-        &envs.from_email_address,
-    ));
+    let signer = Signer::new(
+        envs.local_storage_base_url.clone(),
+        envs.local_storage_signing_key.clone(),
+    );
+    let (storage, storage_url_signer): (Arc<dyn Storage>, Option<Arc<dyn UrlSigner>>) =
+        match envs.storage_driver {
+            StorageDriver::S3 => (
+                Arc::new(s3::Adapter::new(
+                    aws_sdk_s3::Client::new(&aws_config),
+                    aws_config.credentials_provider(),
+                    envs.s3_bucket_name.clone(),
+                )),
+                None,
+            ),
+            StorageDriver::Local => (
+                Arc::new(local_storage::fs::Adapter::new(
+                    envs.local_storage_dir.clone().into(),
+                    signer.clone(),
+                )),
+                Some(Arc::new(signer)),
+            ),
+            StorageDriver::Memory => (
+                Arc::new(local_storage::memory::Adapter::new(signer.clone())),
+                Some(Arc::new(signer)),
+            ),
+        };
+    // ローカルでは送信せずにファイルへ書き出す
+    let (mail, mail_outbox): (Arc<dyn Mail>, Option<Arc<dyn MailOutbox>>) = if env::Env::is_local()
+    {
+        let adapter = Arc::new(local_mail::Adapter::new(
+            envs.local_mail_dir.clone().into(),
+            &envs.from_email_address,
+        ));
+        (adapter.clone(), Some(adapter))
+    } else {
+        (
+            Arc::new(infra::ses::Adapter::new(
+                aws_sdk_sesv2::Client::new(&aws_config),
+                &envs.from_email_address,
+            )),
+            None,
+        )
+    };
     let error_notifier: Arc<dyn ErrorNotifier> = Arc::new(sentry_adapter::Adapter::new(
         ::sentry::Client::from_config(::sentry::apply_defaults({
             let mut options = ::sentry::ClientOptions::default();
@@ -104,12 +174,24 @@ pub async fn app() -> AppResult<&'static App> {
         aws_sdk_cognitoidentityprovider::Client::new(&aws_config),
         envs.cognito_admin_user_pool_id.clone(),
     ));
-    let sns_task_queue: Arc<dyn TaskQueue> =
-        Arc::new(sns::Adapter::new(aws_sdk_sns::Client::new(&aws_config)));
-    let sqs_task_queue: Arc<dyn TaskQueue> =
-        Arc::new(sqs::Adapter::new(aws_sdk_sqs::Client::new(&aws_config)));
+    let sqs = Arc::new(sqs::Adapter::new(aws_sdk_sqs::Client::new(&aws_config)));
+    // ローカルではSNS/SQSを経由せず、APIプロセス内でタスクを実行する
+    let (sns_task_queue, sqs_task_queue): (Arc<dyn TaskQueue>, Arc<dyn TaskQueue>) =
+        if env::Env::is_local() {
+            (
+                Arc::new(local_queue::Adapter::new(Consumer::AsyncSns)),
+                Arc::new(local_queue::Adapter::new(Consumer::AsyncSqs)),
+            )
+        } else {
+            (
+                Arc::new(sns::Adapter::new(aws_sdk_sns::Client::new(&aws_config))),
+                sqs.clone(),
+            )
+        };
+    let sqs_queue_reader: Arc<dyn QueueReader> = sqs;
     let remote_function: Arc<dyn RemoteFunction> = Arc::new(lambda::Adapter::new(
         aws_sdk_lambda::Client::new(&aws_config),
+        std::time::Duration::from_secs(envs.remote_function_timeout_secs),
     ));
 
     let db_session: Arc<dyn DBSession> =
@@ -118,61 +200,94 @@ pub async fn app() -> AppResult<&'static App> {
     let order_repository: Arc<dyn OrderRepository> = Arc::new(repository::order::Repository::new());
     let order_detail_repository: Arc<dyn OrderDetailRepository> =
         Arc::new(repository::order_detail::Repository::new());
+    let api_key_repository: Arc<dyn ApiKeyRepository> =
+        Arc::new(repository::api_key::Repository::new());
+    let user_deletion_request_repository: Arc<dyn DeletionRequestRepository> =
+        Arc::new(repository::user_deletion_request::Repository::new());
+    let user_data_export_repository: Arc<dyn DataExportRepository> =
+        Arc::new(repository::user_data_export::Repository::new());
+    let asset_repository: Arc<dyn AssetRepository> = Arc::new(repository::asset::Repository::new());
+    let email_suppression_repository: Arc<dyn EmailSuppressionRepository> =
+        Arc::new(repository::email_suppression::Repository::new());
+    let device_token_repository: Arc<dyn DeviceTokenRepository> =
+        Arc::new(repository::device_token::Repository::new());
+    let notification_repository: Arc<dyn NotificationRepository> =
+        Arc::new(repository::notification::Repository::new());
+    let notification_preference_repository: Arc<dyn NotificationPreferenceRepository> =
+        Arc::new(repository::notification_preference::Repository::new());
+    let scheduled_task_repository: Arc<dyn ScheduledTaskRepository> =
+        Arc::new(repository::scheduled_task::Repository::new());
+    let processed_task_repository: Arc<dyn ProcessedTaskRepository> =
+        Arc::new(repository::processed_task::Repository::new());
 
-    let user_auth: Option<Arc<dyn UserAuth>> = match (
-        envs.google_project_id.clone(),
-        envs.google_application_credentials.clone(),
-    ) {
-        (Some(project_id), Some(cred)) => {
-            let credentials_json = serde_json::to_string(&cred).map_err(Internal.from_srcf())?;
-            std::fs::write("/tmp/gcp-key.json", credentials_json).map_err(Internal.from_srcf())?;
-            unsafe {
-                std::env::set_var("GOOGLE_APPLICATION_CREDENTIALS", "/tmp/gcp-key.json");
-            }
+    let mail: Arc<dyn Mail> = Arc::new(mail_suppression::Adapter::new(
+        mail,
+        db_session.clone(),
+        email_suppression_repository.clone(),
+    ));
+
+    let (user_auth, push_notifier): (Option<Arc<dyn UserAuth>>, Option<Arc<dyn PushNotifier>>) =
+        match (
+            envs.google_project_id.clone(),
+            envs.google_application_credentials.clone(),
+        ) {
+            (Some(project_id), Some(cred)) => {
+                let credentials_json =
+                    serde_json::to_string(&cred).map_err(Internal.from_srcf())?;
+                std::fs::write("/tmp/gcp-key.json", credentials_json)
+                    .map_err(Internal.from_srcf())?;
+                unsafe {
+                    std::env::set_var("GOOGLE_APPLICATION_CREDENTIALS", "/tmp/gcp-key.json");
+                }
 
-            let google_account = ServiceAccountAuthenticator::with_client(
-                cred.clone(),
-                CustomHyperClientBuilder::from(
-                    hyper_util::client::legacy::Client::builder(
-                        hyper_util::rt::TokioExecutor::new(),
-                    )
-                    .build(
-                        hyper_rustls::HttpsConnectorBuilder::new()
-                            .with_native_roots()
-                            .unwrap()
-                            .https_or_http()
-                            .enable_http1()
-                            .enable_http2()
-                            .build(),
+                let google_account = ServiceAccountAuthenticator::with_client(
+                    cred.clone(),
+                    CustomHyperClientBuilder::from(
+                        hyper_util::client::legacy::Client::builder(
+                            hyper_util::rt::TokioExecutor::new(),
+                        )
+                        .build(
+                            hyper_rustls::HttpsConnectorBuilder::new()
+                                .with_native_roots()
+                                .unwrap()
+                                .https_or_http()
+                                .enable_http1()
+                                .enable_http2()
+                                .build(),
+                        ),
                     ),
-                ),
-            )
-            .build()
-            .await
-            .unwrap();
-
-            let google_http_conn =
-                hyper_util::client::legacy::Client::builder(hyper_util::rt::TokioExecutor::new())
-                    .build(
-                        hyper_rustls::HttpsConnectorBuilder::new()
-                            .with_native_roots()
-                            .unwrap()
-                            .https_or_http()
-                            .enable_http1()
-                            .enable_http2()
-                            .build(),
-                    );
-
-            let firebase_auth = firebase::auth::Adapter::new(
-                project_id,
-                IdentityToolkit::new(google_http_conn.clone(), google_account.clone()),
-            )
-            .await;
+                )
+                .build()
+                .await
+                .unwrap();
 
-            Some(Arc::new(firebase_auth))
-        }
-        _ => None,
-    };
+                let google_http_conn = hyper_util::client::legacy::Client::builder(
+                    hyper_util::rt::TokioExecutor::new(),
+                )
+                .build(
+                    hyper_rustls::HttpsConnectorBuilder::new()
+                        .with_native_roots()
+                        .unwrap()
+                        .https_or_http()
+                        .enable_http1()
+                        .enable_http2()
+                        .build(),
+                );
+
+                let push_notifier = firebase::messaging::Adapter::new(
+                    project_id.clone(),
+                    FirebaseCloudMessaging::new(google_http_conn.clone(), google_account.clone()),
+                );
+                let firebase_auth = firebase::auth::Adapter::new(
+                    project_id,
+                    IdentityToolkit::new(google_http_conn.clone(), google_account.clone()),
+                )
+                .await;
+
+                (Some(Arc::new(firebase_auth)), Some(Arc::new(push_notifier)))
+            }
+            _ => (None, None),
+        };
 
     let image_cdn: Option<Arc<dyn ImageCdn>> =
         if let (Some(domain), Some(key_pair_id), Some(private_key)) = (
@@ -181,9 +296,12 @@ pub async fn app() -> AppResult<&'static App> {
             envs.cloudfront_private_key.clone(),
         ) {
             Some(Arc::new(cloudfront::Adapter::new(
+                aws_sdk_cloudfront::Client::new(&aws_config),
                 domain,
                 key_pair_id,
                 private_key,
+                envs.cloudfront_distribution_id.clone(),
+                envs.cloudfront_cookie_domain.clone(),
             )?))
         } else {
             None
@@ -192,19 +310,33 @@ pub async fn app() -> AppResult<&'static App> {
     let app = App {
         env: envs,
         storage,
+        storage_url_signer,
         mail,
+        mail_outbox,
         error_notifier,
         admin_auth,
         sns_task_queue,
         sqs_task_queue,
+        sqs_queue_reader,
         remote_function,
         db_session,
         user_repository,
         order_repository,
         order_detail_repository,
+        api_key_repository,
+        user_deletion_request_repository,
+        user_data_export_repository,
+        asset_repository,
+        email_suppression_repository,
+        device_token_repository,
+        notification_repository,
+        notification_preference_repository,
+        scheduled_task_repository,
+        processed_task_repository,
 
         image_cdn,
         user_auth,
+        push_notifier,
     };
 
     APP.set(app).unwrap();
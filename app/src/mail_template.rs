@@ -0,0 +1,165 @@
+use crate::AppResult;
+use crate::errors::Kind::*;
+use async_graphql::Enum;
+use handlebars::{Handlebars, no_escape};
+use once_cell::sync::Lazy;
+use serde_json::{Value, json};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter, EnumString};
+
+// テンプレートはバイナリに埋め込む。templates/mail/{id}/{locale}.{subject.txt,html,txt}
+macro_rules! template {
+    ($id:literal, $locale:literal) => {
+        [
+            (
+                concat!($id, "/", $locale, ".subject"),
+                include_str!(concat!(
+                    "../templates/mail/",
+                    $id,
+                    "/",
+                    $locale,
+                    ".subject.txt"
+                )),
+            ),
+            (
+                concat!($id, "/", $locale, ".html"),
+                include_str!(concat!("../templates/mail/", $id, "/", $locale, ".html")),
+            ),
+            (
+                concat!($id, "/", $locale, ".txt"),
+                include_str!(concat!("../templates/mail/", $id, "/", $locale, ".txt")),
+            ),
+        ]
+    };
+}
+
+const TEMPLATES: &[[(&str, &str); 3]] = &[
+    template!("data_export_ready", "ja"),
+    template!("data_export_ready", "en"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString, EnumIter, Enum)]
+#[strum(serialize_all = "snake_case")]
+pub enum TemplateId {
+    DataExportReady,
+}
+impl TemplateId {
+    // プレビュー用のサンプル値
+    pub fn sample_context(&self) -> Value {
+        match self {
+            TemplateId::DataExportReady => json!({
+                "url": "https://example.com/export/sample.zip",
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Display, EnumString, Enum)]
+#[strum(serialize_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Ja,
+    En,
+}
+
+#[derive(Debug, Clone)]
+pub struct TemplateContext {
+    pub locale: Locale,
+    pub values: Value,
+}
+impl TemplateContext {
+    pub fn new(values: Value) -> Self {
+        Self {
+            locale: Locale::default(),
+            values,
+        }
+    }
+
+    pub fn with_locale(self, locale: Locale) -> Self {
+        Self { locale, ..self }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RenderedMail {
+    pub subject: String,
+    pub html: String,
+    pub text: String,
+}
+
+// HTMLはエスケープし、件名とテキストパートはそのまま出力する
+static HTML: Lazy<Handlebars<'static>> = Lazy::new(|| {
+    let mut hb = Handlebars::new();
+    hb.set_strict_mode(true);
+    hb.register_partial("html_layout", include_str!("../templates/mail/layout.html"))
+        .expect("failed to register html layout");
+    for [_, (name, body), _] in TEMPLATES {
+        hb.register_template_string(name, *body)
+            .expect("failed to register html template");
+    }
+    hb
+});
+static TEXT: Lazy<Handlebars<'static>> = Lazy::new(|| {
+    let mut hb = Handlebars::new();
+    hb.set_strict_mode(true);
+    hb.register_escape_fn(no_escape);
+    hb.register_partial("text_layout", include_str!("../templates/mail/layout.txt"))
+        .expect("failed to register text layout");
+    for [(subject_name, subject), _, (name, body)] in TEMPLATES {
+        hb.register_template_string(subject_name, *subject)
+            .expect("failed to register subject template");
+        hb.register_template_string(name, *body)
+            .expect("failed to register text template");
+    }
+    hb
+});
+
+pub fn render(template_id: TemplateId, context: &TemplateContext) -> AppResult<RenderedMail> {
+    // 対応する言語のテンプレートがなければ日本語にフォールバックする
+    let locale = if TEXT.has_template(&template_name(template_id, context.locale, "txt")) {
+        context.locale
+    } else {
+        Locale::default()
+    };
+
+    let mut data = match &context.values {
+        Value::Object(v) => v.clone(),
+        Value::Null => Default::default(),
+        _ => return Err(Internal.with("template context must be an object")),
+    };
+    data.insert("locale".to_string(), json!(locale.to_string()));
+
+    let subject = TEXT
+        .render(&template_name(template_id, locale, "subject"), &data)
+        .map_err(Internal.from_srcf())?
+        .trim()
+        .to_string();
+    data.insert("subject".to_string(), json!(subject));
+
+    let html = HTML
+        .render(&template_name(template_id, locale, "html"), &data)
+        .map_err(Internal.from_srcf())?;
+    let text = TEXT
+        .render(&template_name(template_id, locale, "txt"), &data)
+        .map_err(Internal.from_srcf())?;
+
+    Ok(RenderedMail {
+        subject,
+        html,
+        text: text.trim().to_string(),
+    })
+}
+
+// 開発用にサンプル値で描画する
+pub fn preview(template_id: TemplateId, locale: Locale) -> AppResult<RenderedMail> {
+    let context = TemplateContext::new(template_id.sample_context()).with_locale(locale);
+    render(template_id, &context)
+}
+
+pub fn template_ids() -> Vec<TemplateId> {
+    TemplateId::iter().collect()
+}
+
+fn template_name(template_id: TemplateId, locale: Locale, kind: &str) -> String {
+    format!("{}/{}.{}", template_id, locale, kind)
+}
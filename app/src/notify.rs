@@ -0,0 +1,121 @@
+use crate::adapter::PushMessage;
+use crate::domain::notification::preference::Channel;
+use crate::domain::notification::{Category, Notification};
+use crate::domain::types::email::Email;
+use crate::domain::types::time::now;
+use crate::domain::user;
+use crate::errors::Kind::Internal;
+use crate::errors::NotFoundToNone;
+use crate::mail_template::{TemplateContext, TemplateId};
+use crate::{App, AppResult, push};
+
+// 1件の通知をアプリ内・プッシュ・メールへまとめて配信する
+pub struct Delivery {
+    pub notification: Notification,
+    pub push: Option<PushMessage>,
+    pub mail: Option<(TemplateId, TemplateContext)>,
+}
+impl Delivery {
+    pub fn new(notification: Notification) -> Self {
+        Self {
+            notification,
+            push: None,
+            mail: None,
+        }
+    }
+
+    pub fn push(self, message: PushMessage) -> Self {
+        Self {
+            push: Some(message),
+            ..self
+        }
+    }
+
+    pub fn mail(self, template_id: TemplateId, context: TemplateContext) -> Self {
+        Self {
+            mail: Some((template_id, context)),
+            ..self
+        }
+    }
+}
+
+// アプリ内通知の保存に失敗した場合のみエラーを返す。プッシュ・メールの失敗はログに留める
+pub async fn deliver(app: &App, delivery: Delivery) -> AppResult<()> {
+    let Delivery {
+        notification,
+        push,
+        mail,
+    } = delivery;
+    let user_id = notification.user_id.clone();
+    let category = notification.notification_type.category();
+    let notification_id = notification.id.to_string();
+
+    let tx = app.db_session.begin_tx().await?;
+    app.notification_repository
+        .insert(tx.conn(), notification)
+        .await?;
+    tx.commit().await?;
+
+    if let Some(message) = push {
+        let message = message.data("notification_id", notification_id);
+        if let Err(err) = push::send_to_user(app, &user_id, category, &message).await {
+            tracing::error!("failed to send push notification: {:?}", err);
+        }
+    }
+    if let Some((template_id, context)) = mail {
+        if let Err(err) = send_mail(app, &user_id, category, template_id, context).await {
+            tracing::error!("failed to send notification mail: {:?}", err);
+        }
+    }
+    Ok(())
+}
+
+// ユーザー宛のメールは受信設定を確認してから送る
+pub async fn send_mail(
+    app: &App,
+    user_id: &user::Id,
+    category: Category,
+    template_id: TemplateId,
+    context: TemplateContext,
+) -> AppResult<()> {
+    if !is_allowed(app, user_id, Channel::Email, category).await? {
+        return Ok(());
+    }
+    let Some(auth) = &app.user_auth else {
+        tracing::warn!("user auth is not configured, skip notification mail");
+        return Ok(());
+    };
+    let principal = auth.get(user_id).await?;
+    let Some(email) = principal.email else {
+        tracing::warn!("user {} has no email address", user_id);
+        return Ok(());
+    };
+    let email: Email = email.try_into().map_err(Internal.withf())?;
+    app.mail.send_template(email, template_id, context).await
+}
+
+// 受信設定でオプトアウトされている、またはおやすみ時間中の場合は false を返してログに残す
+pub async fn is_allowed(
+    app: &App,
+    user_id: &user::Id,
+    channel: Channel,
+    category: Category,
+) -> AppResult<bool> {
+    let preference = app
+        .notification_preference_repository
+        .get_by_user(app.db_session.conn(), user_id)
+        .await
+        .not_found_to_none()?;
+    let allowed = preference
+        .map(|v| v.allows(channel, category, now()))
+        .unwrap_or(true);
+    if !allowed {
+        tracing::info!(
+            "skip {} notification for user {} by preference (category: {})",
+            channel,
+            user_id,
+            category
+        );
+    }
+    Ok(allowed)
+}
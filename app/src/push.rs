@@ -0,0 +1,42 @@
+use crate::adapter::PushMessage;
+use crate::domain::notification::Category;
+use crate::domain::notification::preference::Channel;
+use crate::domain::user;
+use crate::{App, AppResult, notify};
+
+// ユーザーの全端末へ送信し、FCMが無効と報告したトークンを削除する
+pub async fn send_to_user(
+    app: &App,
+    user_id: &user::Id,
+    category: Category,
+    message: &PushMessage,
+) -> AppResult<()> {
+    let Some(notifier) = &app.push_notifier else {
+        tracing::warn!("push notifier is not configured, skip push notification");
+        return Ok(());
+    };
+    if !notify::is_allowed(app, user_id, Channel::Push, category).await? {
+        return Ok(());
+    }
+    let tokens = app
+        .device_token_repository
+        .find_by_user(app.db_session.conn(), user_id)
+        .await?;
+    if tokens.is_empty() {
+        return Ok(());
+    }
+
+    let result = notifier
+        .send_multicast(tokens.into_iter().map(|v| v.token).collect(), message)
+        .await?;
+    if !result.unregistered_tokens.is_empty() {
+        let tx = app.db_session.begin_tx().await?;
+        let deleted = app
+            .device_token_repository
+            .delete_by_tokens(tx.conn(), result.unregistered_tokens)
+            .await?;
+        tx.commit().await?;
+        tracing::info!("removed {} unregistered device tokens", deleted);
+    }
+    Ok(())
+}
@@ -0,0 +1,308 @@
+mod delete_user;
+mod export_user_data;
+mod process_image;
+mod scheduled_task;
+mod ses_notification;
+
+use crate::adapter::{BatchEntry, BatchPublishResult, MAX_TASK_DELAY};
+use crate::domain::processed_task::{self as processed, Consumer, ProcessedTask};
+use crate::domain::scheduled_task::{self as scheduled, ScheduledTask};
+use crate::domain::types::ses_notification::SesNotification;
+use crate::domain::types::task::{AsyncTaskMessage, AsyncTaskPayload, Priority, TaskAttributes};
+use crate::domain::types::time::now;
+use crate::errors::Kind::{BadRequest, Duplicate, Internal};
+use crate::errors::NotFoundToNone;
+use crate::{App, AppResult};
+use chrono::Duration;
+
+// async_sns_fn / async_sqs_fn から呼ばれるタスクハンドラ
+pub async fn handle(app: &App, payload: AsyncTaskPayload) -> AppResult<()> {
+    match payload {
+        AsyncTaskPayload::Sample { name } => {
+            tracing::info!("Task name: {}", name);
+            Ok(())
+        }
+        AsyncTaskPayload::DeleteUser {
+            deletion_request_id,
+        } => delete_user::exec(app, deletion_request_id.into()).await,
+        AsyncTaskPayload::ExportUserData { data_export_id } => {
+            export_user_data::exec(app, data_export_id.into()).await
+        }
+        AsyncTaskPayload::ProcessImage { asset_id } => {
+            process_image::exec(app, asset_id.into()).await
+        }
+        AsyncTaskPayload::Scheduled { scheduled_task_id } => {
+            scheduled_task::exec(app, scheduled_task_id.into()).await
+        }
+    }
+}
+
+// 処理中の記録がこれより古い場合は、処理していた関数が異常終了したとみなして取り直す(Lambdaの最大実行時間)
+const CLAIM_TIMEOUT_MINUTES: i64 = 15;
+
+// async_sns_fn / async_sqs_fn が受け取ったメッセージを処理する。
+// 冪等キーの記録を処理の前に作り、同じメッセージが重複して届いても並行して処理されないようにする。
+// 失敗した場合は記録を消して、再配信で改めて処理できるようにする
+pub async fn handle_message(
+    app: &App,
+    consumer: Consumer,
+    message: AsyncTaskMessage,
+) -> AppResult<()> {
+    let Some(key) = message.idempotency_key else {
+        return handle(app, message.payload).await;
+    };
+
+    let task = ProcessedTask::new(consumer, &key, message.payload.task_type());
+    if !claim(app, &task).await? {
+        tracing::info!("skip already processed task {}", task.id);
+        return Ok(());
+    }
+
+    let repository = &app.processed_task_repository;
+    match handle(app, message.payload).await {
+        Ok(_) => {
+            repository
+                .update(app.db_session.conn(), task.complete())
+                .await
+        }
+        Err(err) => {
+            // 消せなかった場合も CLAIM_TIMEOUT_MINUTES 後の再配信で取り直される
+            if let Err(e) = repository.delete(app.db_session.conn(), &task.id).await {
+                tracing::warn!("failed to release processed task {}: {:?}", task.id, e);
+            }
+            Err(err)
+        }
+    }
+}
+
+// 処理する権利を得られた場合は true、処理済みの場合は false を返す。
+// 他の配信が処理中の場合はエラーにして、再配信の時点で改めて判定する
+async fn claim(app: &App, task: &ProcessedTask) -> AppResult<bool> {
+    let repository = &app.processed_task_repository;
+    match repository.insert(app.db_session.conn(), task.clone()).await {
+        Ok(_) => return Ok(true),
+        Err(err) if err.kind == Duplicate => {}
+        Err(err) => return Err(err),
+    }
+
+    let current = repository
+        .get(app.db_session.conn(), &task.id)
+        .await
+        .not_found_to_none()?;
+    if current.is_some_and(|v| v.status == processed::Status::Completed) {
+        return Ok(false);
+    }
+    if repository
+        .reclaim(
+            app.db_session.conn(),
+            &task.id,
+            now() - Duration::minutes(CLAIM_TIMEOUT_MINUTES),
+        )
+        .await?
+    {
+        tracing::warn!("reclaim stale processed task {}", task.id);
+        return Ok(true);
+    }
+    Err(Duplicate.with(format!("task {} is being processed", task.id)))
+}
+
+// async_sns_fn が受け取ったメッセージを処理する。SESのイベントも同じ関数に届く
+pub async fn handle_sns_message(
+    app: &App,
+    message: &str,
+    attributes: Option<TaskAttributes>,
+) -> AppResult<()> {
+    if let Ok(notification) = serde_json::from_str::<SesNotification>(message) {
+        return handle_ses_notification(app, notification).await;
+    }
+
+    let message: AsyncTaskMessage = serde_json::from_str(message)
+        .map_err(|e| BadRequest.with("failed to parse message").with_src(e))?;
+    // フィルターポリシーの対象と実際のタスクが食い違っていないか確認する
+    if let Some(attributes) = attributes {
+        if attributes.task_type != message.payload.task_type() {
+            return Err(BadRequest.with(format!(
+                "task_type attribute {} does not match payload {}",
+                attributes.task_type,
+                message.payload.task_type()
+            )));
+        }
+    }
+    handle_message(app, Consumer::AsyncSns, message).await
+}
+
+// SNS経由で届くSESのバウンス・苦情通知
+pub async fn handle_ses_notification(app: &App, notification: SesNotification) -> AppResult<()> {
+    ses_notification::exec(app, notification).await
+}
+
+// 失敗時にリトライさせたいタスクはSQS経由で実行する
+pub async fn enqueue(app: &App, payload: AsyncTaskPayload) -> AppResult<()> {
+    publish(app, AsyncTaskMessage::new(payload)).await
+}
+
+// 冪等キーを指定して投入する。同じキーのタスクは何度投入しても一度しか実行されない
+pub async fn enqueue_with_key(
+    app: &App,
+    payload: AsyncTaskPayload,
+    key: impl Into<String>,
+) -> AppResult<()> {
+    publish(app, AsyncTaskMessage::with_key(payload, key)).await
+}
+
+// 複数のLambdaに配信したいタスクはSNSへ発行する。各サブスクリプションは属性で受け取るタスクを絞り込める
+// task_type は payload から決めるため、呼び出し側は優先度とテナントのみ指定する
+pub async fn publish_to_topic(
+    app: &App,
+    payload: AsyncTaskPayload,
+    priority: Priority,
+    tenant: Option<String>,
+) -> AppResult<()> {
+    let attributes = TaskAttributes::new(&payload).priority(priority);
+    let attributes = match tenant {
+        Some(tenant) => attributes.tenant(tenant),
+        None => attributes,
+    };
+    app.sns_task_queue
+        .publish_with_attributes(
+            serde_json::to_value(AsyncTaskMessage::new(payload)).map_err(Internal.from_srcf())?,
+            &app.env.sns_async_task_topic_arn,
+            &attributes,
+        )
+        .await
+}
+
+// 大量のタスクをまとめて投入する。送信できなかったタスクは payloads での位置とともに返す
+pub async fn enqueue_batch(
+    app: &App,
+    payloads: Vec<AsyncTaskPayload>,
+) -> AppResult<BatchPublishResult> {
+    let queue_url = &app.env.sqs_async_task_queue_url;
+    let entries = payloads
+        .into_iter()
+        .map(|payload| {
+            let message = AsyncTaskMessage::new(payload);
+            let input = serde_json::to_value(&message).map_err(Internal.from_srcf())?;
+            Ok(match &message.idempotency_key {
+                Some(key) if is_fifo(queue_url) => {
+                    BatchEntry::fifo(input, message.payload.group_id(), key)
+                }
+                _ => BatchEntry::new(input),
+            })
+        })
+        .collect::<AppResult<Vec<_>>>()?;
+
+    let result = app.sqs_task_queue.publish_batch(entries, queue_url).await?;
+    for failure in &result.failures {
+        tracing::warn!(
+            "failed to enqueue task: index={}, code={}, message={:?}",
+            failure.index,
+            failure.code,
+            failure.message
+        );
+    }
+    Ok(result)
+}
+
+pub(crate) async fn publish(app: &App, message: AsyncTaskMessage) -> AppResult<()> {
+    let queue_url = &app.env.sqs_async_task_queue_url;
+    let input = serde_json::to_value(&message).map_err(Internal.from_srcf())?;
+    match &message.idempotency_key {
+        Some(key) if is_fifo(queue_url) => {
+            app.sqs_task_queue
+                .publish_fifo(input, queue_url, message.payload.group_id(), key)
+                .await
+        }
+        _ => app.sqs_task_queue.publish(input, queue_url).await,
+    }
+}
+
+fn is_fifo(queue_url: &str) -> bool {
+    queue_url.ends_with(".fifo")
+}
+
+// 実行時刻までの残りがこの範囲に入ったタスクをSQSへ投入する
+// FIFOキューはメッセージ単位の遅延に対応していないため、実行時刻になってから投入する
+pub(crate) fn dispatch_window(app: &App) -> Duration {
+    if is_fifo(&app.env.sqs_async_task_queue_url) {
+        Duration::zero()
+    } else {
+        Duration::seconds(MAX_TASK_DELAY.as_secs() as i64)
+    }
+}
+
+// delay 後に実行する。SQSの遅延上限(15分)を超える場合は batch_fn が実行時刻の直前に投入する
+pub async fn enqueue_delayed(
+    app: &App,
+    payload: AsyncTaskPayload,
+    delay: Duration,
+) -> AppResult<scheduled::Id> {
+    if delay < Duration::zero() {
+        return Err(BadRequest.with("delay must not be negative"));
+    }
+    let task = ScheduledTask::new(payload, now() + delay);
+    let id = task.id.clone();
+
+    let tx = app.db_session.begin_tx().await?;
+    app.scheduled_task_repository
+        .insert(tx.conn(), task.clone())
+        .await?;
+    tx.commit().await?;
+
+    // 投入に失敗しても Pending のまま残るため batch_fn が拾い直す
+    if delay <= dispatch_window(app) {
+        if let Err(err) = dispatch_scheduled(app, task).await {
+            tracing::warn!("failed to dispatch scheduled task {}: {:?}", id, err);
+        }
+    }
+    Ok(id)
+}
+
+// 実行前のタスクを取り消す。SQSに投入済みの場合も実行時にスキップされる
+pub async fn cancel_scheduled(app: &App, id: &scheduled::Id) -> AppResult<()> {
+    let tx = app.db_session.begin_tx().await?;
+    let task = app.scheduled_task_repository.get(tx.conn(), id).await?;
+    let task = task.cancel().map_err(BadRequest.withf())?;
+    app.scheduled_task_repository
+        .update(tx.conn(), task)
+        .await?;
+    tx.commit().await
+}
+
+// 実行時刻までの残り時間を DelaySeconds にしてSQSへ投入する
+pub(crate) async fn dispatch_scheduled(app: &App, task: ScheduledTask) -> AppResult<()> {
+    let delay = (task.run_at - now())
+        .to_std()
+        .unwrap_or_default()
+        .min(MAX_TASK_DELAY);
+    // 再投入されても一度しか実行されないよう、タスクIDから冪等キーを決める
+    let message = AsyncTaskMessage::with_key(
+        AsyncTaskPayload::Scheduled {
+            scheduled_task_id: task.id.to_string(),
+        },
+        format!("scheduled-{}", task.id),
+    );
+
+    // 即時実行された場合に Done を上書きしないよう、投入前に状態を進める
+    app.scheduled_task_repository
+        .update(app.db_session.conn(), task.clone().enqueue())
+        .await?;
+    // 実行時刻を過ぎている場合は通常の投入にして、FIFOキューでも送れるようにする
+    let result = if delay.is_zero() {
+        publish(app, message).await
+    } else {
+        app.sqs_task_queue
+            .publish_delayed(
+                serde_json::to_value(&message).map_err(Internal.from_srcf())?,
+                &app.env.sqs_async_task_queue_url,
+                delay,
+            )
+            .await
+    };
+    if result.is_err() {
+        app.scheduled_task_repository
+            .update(app.db_session.conn(), task)
+            .await?;
+    }
+    result
+}
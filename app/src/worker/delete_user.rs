@@ -0,0 +1,113 @@
+use crate::domain::types::asset_key::AssetKey;
+use crate::domain::user::deletion_request::{self, DeletionRequest, OrderPolicy, Step};
+use crate::errors::NotFoundToNone;
+use crate::{App, AppResult};
+
+pub async fn exec(app: &App, id: deletion_request::Id) -> AppResult<()> {
+    let repository = &app.user_deletion_request_repository;
+    let request = repository.get(app.db_session.conn(), &id).await?;
+    if request.step == Step::Done {
+        return Ok(());
+    }
+
+    let mut request = request.start();
+    repository
+        .update(app.db_session.conn(), request.clone())
+        .await?;
+
+    while request.step != Step::Done {
+        if let Err(err) = exec_step(app, &request).await {
+            tracing::error!(
+                "user deletion failed: request_id={}, step={}",
+                request.id,
+                request.step
+            );
+            repository
+                .update(app.db_session.conn(), request.fail(err.to_string()))
+                .await?;
+            return Err(err);
+        }
+        tracing::info!(
+            "user deletion step completed: request_id={}, step={}",
+            request.id,
+            request.step
+        );
+
+        request = request.advance();
+        repository
+            .update(app.db_session.conn(), request.clone())
+            .await?;
+    }
+
+    Ok(())
+}
+
+async fn exec_step(app: &App, request: &DeletionRequest) -> AppResult<()> {
+    let user_id = &request.user_id;
+    match request.step {
+        Step::Orders => {
+            let tx = app.db_session.begin_tx().await?;
+            match app.env.user_deletion_order_policy {
+                OrderPolicy::Delete => {
+                    let orders = app
+                        .order_repository
+                        .find_by_user(tx.conn(), user_id)
+                        .await?;
+                    for order in orders {
+                        app.order_detail_repository
+                            .delete_by_order(tx.conn(), &order.id)
+                            .await?;
+                        app.order_repository.delete(tx.conn(), &order.id).await?;
+                    }
+                }
+                OrderPolicy::Anonymize => {
+                    app.order_repository
+                        .anonymize_by_user(tx.conn(), user_id)
+                        .await?;
+                }
+            }
+            tx.commit().await?;
+        }
+        Step::Storage => {
+            for prefix in AssetKey::user_prefixes(user_id) {
+                let deleted = app.storage.delete_objects_by_prefix(&prefix).await?;
+                tracing::info!("deleted {} objects under {}", deleted, prefix);
+            }
+            // 実体は削除済みでキャッシュも期限で消えるため、無効化の失敗ではステップをやり直さない
+            if let Some(cdn) = &app.image_cdn {
+                let path = format!("/{}*", AssetKey::asset_prefix(user_id));
+                if let Err(err) = cdn.invalidate(vec![path]).await {
+                    tracing::error!("failed to invalidate cdn cache: {:?}", err);
+                }
+            }
+        }
+        Step::Identity => match &app.user_auth {
+            Some(auth) => {
+                auth.delete(user_id).await.not_found_to_none()?;
+            }
+            None => tracing::warn!("user auth is not configured, skip identity deletion"),
+        },
+        Step::Profile => {
+            let tx = app.db_session.begin_tx().await?;
+            app.user_data_export_repository
+                .delete_by_user(tx.conn(), user_id)
+                .await?;
+            app.asset_repository
+                .delete_by_user(tx.conn(), user_id)
+                .await?;
+            app.device_token_repository
+                .delete_by_user(tx.conn(), user_id)
+                .await?;
+            app.notification_repository
+                .delete_by_user(tx.conn(), user_id)
+                .await?;
+            app.notification_preference_repository
+                .delete_by_user(tx.conn(), user_id)
+                .await?;
+            app.user_repository.delete(tx.conn(), user_id).await?;
+            tx.commit().await?;
+        }
+        Step::Done => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,335 @@
+use crate::adapter::{MultipartWriter, PushMessage};
+use crate::domain::notification::{self, Notification};
+use crate::domain::order::Order;
+use crate::domain::order::detail::Detail;
+use crate::domain::types::asset_key::AssetKey;
+use crate::domain::types::time::ToRfc3339;
+use crate::domain::user::User;
+use crate::domain::user::data_export::{self, DataExport, Status};
+use crate::errors::Kind::Internal;
+use crate::mail_template::{TemplateContext, TemplateId};
+use crate::notify::{self, Delivery};
+use crate::{App, AppResult};
+use serde::Serialize;
+use serde_json::json;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::sync::{Arc, Mutex};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+pub async fn exec(app: &App, id: data_export::Id) -> AppResult<()> {
+    let repository = &app.user_data_export_repository;
+    let data_export = repository.get(app.db_session.conn(), &id).await?;
+    // Failed はSQSのリトライで再実行する(delete_user と同様に再開できるようにする)
+    if data_export.status == Status::Completed {
+        return Ok(());
+    }
+
+    let data_export = data_export.start();
+    repository
+        .update(app.db_session.conn(), data_export.clone())
+        .await?;
+
+    match export(app, &data_export).await {
+        Ok(key) => {
+            repository
+                .update(
+                    app.db_session.conn(),
+                    data_export.clone().complete(key.clone()),
+                )
+                .await?;
+            // 通知の失敗でエクスポート自体をやり直さないようにエラーはログに留める
+            if let Err(err) = notify(app, &data_export, &key).await {
+                tracing::error!("failed to send export notification: {:?}", err);
+            }
+            Ok(())
+        }
+        Err(err) => {
+            repository
+                .update(app.db_session.conn(), data_export.fail(err.to_string()))
+                .await?;
+            Err(err)
+        }
+    }
+}
+
+async fn export(app: &App, data_export: &DataExport) -> AppResult<AssetKey> {
+    let user = app
+        .user_repository
+        .get(app.db_session.conn(), &data_export.user_id)
+        .await?;
+    let orders = app
+        .order_repository
+        .find_by_user(app.db_session.conn(), &user.id)
+        .await?;
+    let details = app
+        .order_detail_repository
+        .get_multi_by_order(
+            app.db_session.conn(),
+            orders.iter().map(|v| &v.id).collect(),
+        )
+        .await?;
+    let asset_keys = app
+        .storage
+        .list_object_keys(&AssetKey::asset_prefix(&user.id))
+        .await?;
+
+    let data = export_data(&user, &orders, &details, &asset_keys);
+    let key = AssetKey::export_key(&user.id, format!("{}.zip", data_export.id));
+    // 注文が多いユーザーはアーカイブが大きくなるのでマルチパートでアップロードする
+    let mut writer = MultipartWriter::new(app.storage.as_ref(), &key, "application/zip").await?;
+    if let Err(err) = write_archive(&mut writer, &data).await {
+        writer.abort().await?;
+        return Err(err);
+    }
+    writer.finish().await?;
+
+    Ok(key)
+}
+
+async fn notify(app: &App, data_export: &DataExport, key: &AssetKey) -> AppResult<()> {
+    let url = app.storage.presign_for_get(key).await?;
+    let notification = Notification::new(
+        data_export.user_id.clone(),
+        notification::Type::DataExportReady,
+        json!({ "dataExportId": data_export.id.to_string() }),
+    );
+    notify::deliver(
+        app,
+        Delivery::new(notification)
+            .push(PushMessage::new(
+                "データエクスポートのお知らせ",
+                "データのエクスポートが完了しました",
+            ))
+            .mail(
+                TemplateId::DataExportReady,
+                TemplateContext::new(json!({ "url": url.to_string() })),
+            ),
+    )
+    .await
+}
+
+#[derive(Serialize)]
+struct ExportData {
+    profile: ProfileRow,
+    orders: Vec<OrderRow>,
+    order_details: Vec<OrderDetailRow>,
+    asset_keys: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ProfileRow {
+    id: String,
+    name: String,
+    birthdate: String,
+    gender: String,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Serialize)]
+struct OrderRow {
+    id: String,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Serialize)]
+struct OrderDetailRow {
+    id: String,
+    order_id: String,
+    product_name: String,
+    quantity: u32,
+    created_at: String,
+    updated_at: String,
+}
+
+fn export_data(
+    user: &User,
+    orders: &[Order],
+    details: &[Detail],
+    asset_keys: &[AssetKey],
+) -> ExportData {
+    ExportData {
+        profile: ProfileRow {
+            id: user.id.to_string(),
+            name: user.name.to_string(),
+            birthdate: user.birthdate.to_rfc3339(),
+            gender: user.gender.into(),
+            created_at: user.created_at.to_rfc3339(),
+            updated_at: user.updated_at.to_rfc3339(),
+        },
+        orders: orders
+            .iter()
+            .map(|v| OrderRow {
+                id: v.id.to_string(),
+                created_at: v.created_at.to_rfc3339(),
+                updated_at: v.updated_at.to_rfc3339(),
+            })
+            .collect(),
+        order_details: details
+            .iter()
+            .map(|v| OrderDetailRow {
+                id: v.id.to_string(),
+                order_id: v.order_id.to_string(),
+                product_name: v.product_name.to_string(),
+                quantity: v.quantity,
+                created_at: v.created_at.to_rfc3339(),
+                updated_at: v.updated_at.to_rfc3339(),
+            })
+            .collect(),
+        asset_keys: asset_keys.iter().map(|v| v.to_string()).collect(),
+    }
+}
+
+// アーカイブ全体をメモリに載せないよう、書き終えたファイルから順にアップロードする
+async fn write_archive(writer: &mut MultipartWriter<'_>, data: &ExportData) -> AppResult<()> {
+    let spool = Spool::default();
+    let mut zip = ZipWriter::new(spool.clone());
+    zip.set_flush_on_finish_file(true);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    zip.start_file("data.json", options)
+        .map_err(Internal.from_srcf())?;
+    serde_json::to_writer_pretty(&mut zip, data).map_err(Internal.from_srcf())?;
+
+    zip.start_file("orders.csv", options)
+        .map_err(Internal.from_srcf())?;
+    writer.write(&spool.take_committed()).await?;
+    write_csv_row(&mut zip, &["id", "created_at", "updated_at"])?;
+    for v in &data.orders {
+        write_csv_row(&mut zip, &[&v.id, &v.created_at, &v.updated_at])?;
+    }
+
+    zip.start_file("order_details.csv", options)
+        .map_err(Internal.from_srcf())?;
+    writer.write(&spool.take_committed()).await?;
+    write_csv_row(
+        &mut zip,
+        &[
+            "id",
+            "order_id",
+            "product_name",
+            "quantity",
+            "created_at",
+            "updated_at",
+        ],
+    )?;
+    for v in &data.order_details {
+        write_csv_row(
+            &mut zip,
+            &[
+                &v.id,
+                &v.order_id,
+                &v.product_name,
+                &v.quantity.to_string(),
+                &v.created_at,
+                &v.updated_at,
+            ],
+        )?;
+    }
+
+    zip.finish()
+        .map_err(Internal.from_srcf())?
+        .flush()
+        .map_err(Internal.from_srcf())?;
+    writer.write(&spool.take_committed()).await
+}
+
+// ZipWriter はファイルを書き終えるとローカルヘッダを書き換えに戻るため Seek が必要になる。
+// set_flush_on_finish_file により書き終えたファイルごとに flush されるので、
+// flush 時点より前は確定したものとして取り出し、以降だけをメモリに残す
+#[derive(Clone, Default)]
+struct Spool(Arc<Mutex<SpoolInner>>);
+
+#[derive(Default)]
+struct SpoolInner {
+    // buf の先頭のアーカイブ内での位置
+    offset: u64,
+    buf: Vec<u8>,
+    pos: u64,
+    committed: u64,
+}
+
+impl Spool {
+    fn take_committed(&self) -> Vec<u8> {
+        let mut inner = self.0.lock().unwrap();
+        let len = (inner.committed - inner.offset) as usize;
+        inner.offset = inner.committed;
+        inner.buf.drain(..len).collect()
+    }
+}
+
+impl SpoolInner {
+    // 取り出し済みの範囲には戻れない
+    fn index(&self) -> std::io::Result<usize> {
+        self.pos
+            .checked_sub(self.offset)
+            .map(|v| v as usize)
+            .ok_or_else(|| std::io::Error::other("cannot access data already taken"))
+    }
+}
+
+impl Write for Spool {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut inner = self.0.lock().unwrap();
+        let start = inner.index()?;
+        let end = start + buf.len();
+        if inner.buf.len() < end {
+            inner.buf.resize(end, 0);
+        }
+        inner.buf[start..end].copy_from_slice(buf);
+        inner.pos += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        let mut inner = self.0.lock().unwrap();
+        inner.committed = inner.pos;
+        Ok(())
+    }
+}
+
+impl Seek for Spool {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let mut inner = self.0.lock().unwrap();
+        let end = inner.offset + inner.buf.len() as u64;
+        let pos = match pos {
+            SeekFrom::Start(v) => Some(v),
+            SeekFrom::End(v) => end.checked_add_signed(v),
+            SeekFrom::Current(v) => inner.pos.checked_add_signed(v),
+        }
+        .ok_or_else(|| std::io::Error::other("invalid seek position"))?;
+        inner.pos = pos;
+        Ok(pos)
+    }
+}
+
+// set_flush_on_finish_file を使うために必要。アーカイブを読み直すことはない
+impl Read for Spool {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut inner = self.0.lock().unwrap();
+        let start = inner.index()?.min(inner.buf.len());
+        let len = buf.len().min(inner.buf.len() - start);
+        buf[..len].copy_from_slice(&inner.buf[start..start + len]);
+        inner.pos += len as u64;
+        Ok(len)
+    }
+}
+
+fn write_csv_row(w: &mut impl Write, row: &[&str]) -> AppResult<()> {
+    let line = row
+        .iter()
+        .map(|v| escape_csv(v))
+        .collect::<Vec<_>>()
+        .join(",");
+    write!(w, "{}\r\n", line).map_err(Internal.from_srcf())
+}
+
+fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
@@ -0,0 +1,129 @@
+use crate::domain::asset::{self, Asset, Status};
+use crate::domain::types::image_size::ImageSize;
+use crate::domain::types::upload_policy::UploadPurpose;
+use crate::errors::Kind::*;
+use crate::{App, AppResult};
+use bytes::Bytes;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use std::io::Cursor;
+
+const MIN_DIMENSION: u32 = 16;
+const MAX_DIMENSION: u32 = 10_000;
+const MAX_PIXELS: u64 = 40_000_000;
+
+pub async fn exec(app: &App, id: asset::Id) -> AppResult<()> {
+    let asset = app.asset_repository.get(app.db_session.conn(), &id).await?;
+    if asset.status != Status::Confirmed || !asset.is_image() {
+        return Ok(());
+    }
+
+    // 全体をメモリに読み込むため、アップロードできる上限を超えるものは処理しない
+    let max_size = UploadPurpose::Image.max_size();
+    if asset.size.is_none_or(|v| v > max_size) {
+        tracing::warn!(
+            "reject asset {}: size {:?} exceeds {}",
+            asset.id,
+            asset.size,
+            max_size
+        );
+        return reject(app, asset).await;
+    }
+
+    let original = app.storage.download_object(&asset.key).await?;
+    let (image, format) = match decode(&original) {
+        Ok(v) => v,
+        Err(err) => {
+            tracing::warn!("reject asset {}: {}", asset.id, err);
+            return reject(app, asset).await;
+        }
+    };
+    let (width, height) = (image.width(), image.height());
+
+    // デコードし直した画像で元画像を上書きしてEXIF(GPS等)のメタデータを落とす
+    // 再エンコードで画質やアニメーションは失われるが、位置情報を含む元画像は残さない
+    let stripped = encode(&image, format)?;
+    app.storage
+        .put_object(&asset.key, stripped, format.to_mime_type())
+        .await?;
+    let head = app.storage.head_object(&asset.key).await?;
+
+    for size in [ImageSize::Large, ImageSize::Medium, ImageSize::Small] {
+        let variant = if width > size.width() {
+            image.resize(size.width(), u32::MAX, FilterType::Lanczos3)
+        } else {
+            image.clone()
+        };
+        app.storage
+            .put_object(
+                &asset.key.variant_key(size),
+                encode_webp(&variant)?,
+                "image/webp",
+            )
+            .await?;
+    }
+
+    let asset = asset.processed(width, height, head.content_length, head.e_tag);
+    app.asset_repository
+        .update(app.db_session.conn(), asset)
+        .await
+}
+
+// 不正な画像はリトライしても結果が変わらないので、破棄して正常終了にする
+async fn reject(app: &App, asset: Asset) -> AppResult<()> {
+    app.storage.delete_object(&asset.key).await?;
+    app.asset_repository
+        .update(app.db_session.conn(), asset.reject())
+        .await
+}
+
+fn decode(data: &[u8]) -> Result<(DynamicImage, ImageFormat), String> {
+    let reader = ImageReader::new(Cursor::new(data))
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?;
+    let format = reader
+        .format()
+        .ok_or_else(|| "unknown image format".to_string())?;
+    let mut decoder = reader.into_decoder().map_err(|e| e.to_string())?;
+    // デコード前にヘッダーのサイズを確認し、巨大な画像でメモリを使い切らないようにする
+    let (width, height) = decoder.dimensions();
+    validate_dimensions(width, height)?;
+
+    let orientation = decoder.orientation().map_err(|e| e.to_string())?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(|e| e.to_string())?;
+    image.apply_orientation(orientation);
+    Ok((image, format))
+}
+
+fn validate_dimensions(width: u32, height: u32) -> Result<(), String> {
+    if width < MIN_DIMENSION || height < MIN_DIMENSION {
+        return Err(format!("image is too small: {}x{}", width, height));
+    }
+    if width > MAX_DIMENSION || height > MAX_DIMENSION || width as u64 * height as u64 > MAX_PIXELS
+    {
+        return Err(format!("image is too large: {}x{}", width, height));
+    }
+    Ok(())
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat) -> AppResult<Bytes> {
+    let mut buf = Cursor::new(Vec::new());
+    match format {
+        // JPEGはアルファチャンネルを扱えない
+        ImageFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8()).write_to(&mut buf, format),
+        _ => image.write_to(&mut buf, format),
+    }
+    .map_err(Internal.from_srcf())?;
+    Ok(Bytes::from(buf.into_inner()))
+}
+
+// imageクレートのWebPエンコーダーはロスレスのみ対応
+fn encode_webp(image: &DynamicImage) -> AppResult<Bytes> {
+    let mut buf = Vec::new();
+    let image = DynamicImage::ImageRgba8(image.to_rgba8());
+    image
+        .write_with_encoder(WebPEncoder::new_lossless(&mut buf))
+        .map_err(Internal.from_srcf())?;
+    Ok(Bytes::from(buf))
+}
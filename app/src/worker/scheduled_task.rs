@@ -0,0 +1,20 @@
+use crate::domain::scheduled_task::{self, Status};
+use crate::{App, AppResult};
+
+pub async fn exec(app: &App, id: scheduled_task::Id) -> AppResult<()> {
+    let repository = &app.scheduled_task_repository;
+    let task = repository.get(app.db_session.conn(), &id).await?;
+    // キャンセル済み・諦めたもの・SQSの重複配信で実行済みのものはスキップする
+    if matches!(
+        task.status,
+        Status::Done | Status::Cancelled | Status::Failed
+    ) {
+        tracing::info!("skip scheduled task {} ({})", task.id, task.status);
+        return Ok(());
+    }
+
+    Box::pin(super::handle(app, task.payload.clone())).await?;
+    repository
+        .update(app.db_session.conn(), task.complete())
+        .await
+}
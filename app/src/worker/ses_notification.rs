@@ -0,0 +1,67 @@
+use crate::domain::email_suppression::{EmailSuppression, Reason};
+use crate::domain::types::email::Email;
+use crate::domain::types::ses_notification::{NotificationType, Recipient, SesNotification};
+use crate::{App, AppResult};
+
+// 恒久的なバウンスと苦情のあった宛先を送信停止リストに登録する
+pub async fn exec(app: &App, notification: SesNotification) -> AppResult<()> {
+    let (reason, recipients, detail) = match notification.notification_type {
+        NotificationType::Bounce => {
+            let Some(bounce) = notification.bounce else {
+                return Ok(());
+            };
+            if !bounce.is_permanent() {
+                tracing::info!("skip non-permanent bounce: {}", bounce.bounce_type);
+                return Ok(());
+            }
+            let detail = match &bounce.bounce_sub_type {
+                Some(sub_type) => format!("{}/{}", bounce.bounce_type, sub_type),
+                None => bounce.bounce_type.clone(),
+            };
+            (Reason::Bounce, bounce.bounced_recipients, Some(detail))
+        }
+        NotificationType::Complaint => {
+            let Some(complaint) = notification.complaint else {
+                return Ok(());
+            };
+            (
+                Reason::Complaint,
+                complaint.complained_recipients,
+                complaint.complaint_feedback_type,
+            )
+        }
+        NotificationType::Delivery | NotificationType::Other => return Ok(()),
+    };
+
+    let tx = app.db_session.begin_tx().await?;
+    for Recipient {
+        email_address,
+        diagnostic_code,
+    } in recipients
+    {
+        let email: Email = match extract_address(&email_address).try_into() {
+            Ok(v) => v,
+            Err(err) => {
+                tracing::warn!("invalid recipient {}: {}", email_address, err);
+                continue;
+            }
+        };
+        tracing::info!("suppress {} ({})", email, reason);
+        app.email_suppression_repository
+            .upsert(
+                tx.conn(),
+                EmailSuppression::new(email, reason, diagnostic_code.or(detail.clone())),
+            )
+            .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+// "Name <user@example.com>" 形式の場合はアドレス部分のみを取り出す
+fn extract_address(value: &str) -> String {
+    match (value.rfind('<'), value.rfind('>')) {
+        (Some(start), Some(end)) if start < end => value[start + 1..end].trim().to_string(),
+        _ => value.trim().to_string(),
+    }
+}
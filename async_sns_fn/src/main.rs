@@ -1,6 +1,6 @@
 use anyhow::anyhow;
 use app::AppResult;
-use app::domain::types::task::{AsyncTaskPayload, SnsEventData};
+use app::domain::types::task::SnsEventData;
 use app::errors::Kind::BadRequest;
 use lambda_runtime::{Error, LambdaEvent, service_fn};
 use serde_json::Value;
@@ -31,14 +31,13 @@ async fn bridge(event: LambdaEvent<Value>) -> Result<(), Error> {
     Ok(())
 }
 
-async fn exec(_app: &app::App, payload: Value) -> AppResult<()> {
+async fn exec(app: &app::App, payload: Value) -> AppResult<()> {
     let data: SnsEventData = serde_json::from_value(payload)
         .map_err(|e| BadRequest.with("failed to parse payload").with_src(e))?;
     if let Some(record) = data.records.first() {
-        let task_payload: AsyncTaskPayload = serde_json::from_str(&record.sns.message)
-            .map_err(|e| BadRequest.with("failed to parse message").with_src(e))?;
-
-        tracing::info!("Task name: {}", task_payload.name);
+        // SESのイベントは別トピックから同じ関数に配信される
+        let attributes = record.sns.task_attributes().map_err(BadRequest.withf())?;
+        app::worker::handle_sns_message(app, &record.sns.message, attributes).await?;
     }
 
     Ok(())
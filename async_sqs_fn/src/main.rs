@@ -1,6 +1,7 @@
 use anyhow::anyhow;
 use app::AppResult;
-use app::domain::types::task::{AsyncTaskPayload, SqsEventData};
+use app::domain::processed_task::Consumer;
+use app::domain::types::task::{AsyncTaskMessage, SqsEventData};
 use app::errors::Kind::BadRequest;
 use lambda_runtime::{Error, LambdaEvent, service_fn};
 use serde_json::Value;
@@ -31,14 +32,14 @@ async fn bridge(event: LambdaEvent<Value>) -> Result<(), Error> {
     Ok(())
 }
 
-async fn exec(_app: &app::App, payload: Value) -> AppResult<()> {
+async fn exec(app: &app::App, payload: Value) -> AppResult<()> {
     let data: SqsEventData = serde_json::from_value(payload)
         .map_err(|e| BadRequest.with("failed to parse payload").with_src(e))?;
     if let Some(record) = data.records.first() {
-        let task_payload: AsyncTaskPayload = serde_json::from_str(&record.body)
+        let message: AsyncTaskMessage = serde_json::from_str(&record.body)
             .map_err(|e| BadRequest.with("failed to parse message").with_src(e))?;
 
-        tracing::info!("Task name: {}", task_payload.name);
+        app::worker::handle_message(app, Consumer::AsyncSqs, message).await?;
     }
 
     Ok(())
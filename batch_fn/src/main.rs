@@ -29,8 +29,8 @@ async fn bridge(event: LambdaEvent<Value>) -> Result<(), Error> {
     Ok(())
 }
 
-async fn exec(_app: &app::App, payload: Value) -> AppResult<()> {
+async fn exec(app: &app::App, payload: Value) -> AppResult<()> {
     tracing::info!("Batch task started with payload: {:?}", payload);
 
-    Ok(())
+    app::batch::run(app).await
 }
@@ -0,0 +1,70 @@
+use app::AppResult;
+use app::errors::Kind::BadRequest;
+use serde_json::json;
+
+const USAGE: &str =
+    "usage: dlq list [limit] | dlq redrive <message_id>... | dlq delete <message_id>...";
+
+#[tokio::main]
+async fn main() {
+    let app = match app::app().await {
+        Ok(res) => res,
+        Err(err) => {
+            panic!("Failed to initialize app: {:?}", err);
+        }
+    };
+    app::init_log();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(err) = exec(app, &args).await {
+        eprintln!("{:?}", err);
+        std::process::exit(1);
+    }
+}
+
+async fn exec(app: &app::App, args: &[String]) -> AppResult<()> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err(BadRequest.with(USAGE));
+    };
+    match command.as_str() {
+        "list" => {
+            let limit = match rest.first() {
+                Some(v) => v.parse().map_err(BadRequest.from_srcf())?,
+                None => app::dlq::MAX_MESSAGES,
+            };
+            for dead_letter in app::dlq::list(app, limit).await? {
+                let task = dead_letter.task.as_ref();
+                let line = json!({
+                    "id": dead_letter.message.message_id,
+                    "receiveCount": dead_letter.message.receive_count,
+                    "sentAt": dead_letter.message.sent_at.map(|v| v.to_rfc3339()),
+                    "taskType": task.map(|v| v.payload.task_type()),
+                    "idempotencyKey": task.and_then(|v| v.idempotency_key.clone()),
+                    "body": dead_letter.message.body,
+                });
+                println!("{}", line);
+            }
+            Ok(())
+        }
+        "redrive" if !rest.is_empty() => {
+            let redriven = app::dlq::redrive(app, rest).await?;
+            report("redriven", rest, &redriven);
+            Ok(())
+        }
+        "delete" if !rest.is_empty() => {
+            let deleted = app::dlq::delete(app, rest).await?;
+            report("deleted", rest, &deleted);
+            Ok(())
+        }
+        _ => Err(BadRequest.with(USAGE)),
+    }
+}
+
+fn report(action: &str, requested: &[String], done: &[String]) {
+    for id in done {
+        println!("{}: {}", action, id);
+    }
+    for id in requested.iter().filter(|v| !done.contains(v)) {
+        eprintln!("not found or failed: {}", id);
+    }
+}
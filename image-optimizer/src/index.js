@@ -4,7 +4,13 @@ const { S3Client, GetObjectCommand } = require('@aws-sdk/client-s3');
 const sharp = require('sharp');
 
 const s3 = new S3Client();
-const MAX_SIZE = 1200;
+// app/src/domain/types/image_transform.rs の許可リストと合わせる
+const MAX_SIZE = 2400;
+// contain は枠内に収める sharp の inside に対応する
+const FITS = { cover: 'cover', contain: 'inside', outside: 'outside' };
+const FORMATS = { webp: 'image/webp', avif: 'image/avif', jpeg: 'image/jpeg' };
+const QUALITIES = [50, 75, 90];
+const DPRS = [1, 2, 3];
 
 const errorResponse = (response, status, message) => {
     response.status = status;
@@ -30,14 +36,21 @@ exports.handler = async (event) => {
 
     if (!w && !h) return response;
 
-    const width = parseSize(w);
-    const height = parseSize(h);
+    const dpr = params.has('dpr') ? parseInt(params.get('dpr'), 10) : 1;
+    if (!DPRS.includes(dpr)) return errorResponse(response, '400', 'Invalid dpr.');
 
-    if ((w && !width) || (h && !height)) {
+    const width = w ? parseSize(w) : null;
+    const height = h ? parseSize(h) : null;
+    if ((w && !width) || (h && !height) || (width && width * dpr > MAX_SIZE) || (height && height * dpr > MAX_SIZE)) {
         return errorResponse(response, '400', `Size must be 1-${MAX_SIZE}.`);
     }
 
-    const targetSize = width || height;
+    const fit = params.get('fit') || 'contain';
+    const format = params.get('fm') || 'webp';
+    const quality = params.has('q') ? parseInt(params.get('q'), 10) : 75;
+    if (!FITS[fit] || !FORMATS[format] || !QUALITIES.includes(quality)) {
+        return errorResponse(response, '400', 'Invalid transform.');
+    }
 
     try {
         const bucketName = request.origin.s3.domainName.split('.')[0];
@@ -46,14 +59,18 @@ exports.handler = async (event) => {
         const { Body } = await s3.send(new GetObjectCommand({ Bucket: bucketName, Key: key }));
         const imageBuffer = Buffer.from(await Body.transformToByteArray());
 
+        // rotate() でEXIFの向きを反映し、出力からはメタデータを落とす
         const buffer = await sharp(imageBuffer)
             .rotate()
-            .resize(targetSize, targetSize, { fit: 'outside', withoutEnlargement: true })
-            .webp()
+            .resize(width && width * dpr, height && height * dpr, {
+                fit: FITS[fit],
+                withoutEnlargement: true,
+            })
+            .toFormat(format, { quality })
             .toBuffer();
 
         response.status = '200';
-        response.headers['content-type'] = [{ key: 'Content-Type', value: 'image/webp' }];
+        response.headers['content-type'] = [{ key: 'Content-Type', value: FORMATS[format] }];
         response.body = buffer.toString('base64');
         response.bodyEncoding = 'base64';
         return response;
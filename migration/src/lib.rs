@@ -3,6 +3,20 @@ pub use sea_orm_migration::prelude::*;
 mod m20250907_074340_create_users;
 mod m20250907_074341_create_orders;
 mod m20250907_074342_create_order_details;
+mod m20261018_090000_create_api_keys;
+mod m20261018_100000_create_deletion_requests;
+mod m20261018_100100_alter_orders_user_id_nullable;
+mod m20261018_110000_create_data_exports;
+mod m20261018_120000_create_assets;
+mod m20261018_130000_alter_assets_add_dimensions;
+mod m20261019_090000_create_email_suppressions;
+mod m20261019_100000_create_device_tokens;
+mod m20261019_110000_create_notifications;
+mod m20261019_120000_create_notification_preferences;
+mod m20261019_130000_create_scheduled_tasks;
+mod m20261019_140000_create_processed_tasks;
+mod m20261019_150000_alter_processed_tasks_add_status;
+mod m20261019_160000_alter_scheduled_tasks_add_attempts;
 
 pub struct Migrator;
 
@@ -13,6 +27,20 @@ impl MigratorTrait for Migrator {
             Box::new(m20250907_074340_create_users::Migration),
             Box::new(m20250907_074341_create_orders::Migration),
             Box::new(m20250907_074342_create_order_details::Migration),
+            Box::new(m20261018_090000_create_api_keys::Migration),
+            Box::new(m20261018_100000_create_deletion_requests::Migration),
+            Box::new(m20261018_100100_alter_orders_user_id_nullable::Migration),
+            Box::new(m20261018_110000_create_data_exports::Migration),
+            Box::new(m20261018_120000_create_assets::Migration),
+            Box::new(m20261018_130000_alter_assets_add_dimensions::Migration),
+            Box::new(m20261019_090000_create_email_suppressions::Migration),
+            Box::new(m20261019_100000_create_device_tokens::Migration),
+            Box::new(m20261019_110000_create_notifications::Migration),
+            Box::new(m20261019_120000_create_notification_preferences::Migration),
+            Box::new(m20261019_130000_create_scheduled_tasks::Migration),
+            Box::new(m20261019_140000_create_processed_tasks::Migration),
+            Box::new(m20261019_150000_alter_processed_tasks_add_status::Migration),
+            Box::new(m20261019_160000_alter_scheduled_tasks_add_attempts::Migration),
         ]
     }
 }
@@ -0,0 +1,55 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ApiKeys::Table)
+                    .if_not_exists()
+                    .col(string(ApiKeys::Id).primary_key())
+                    .col(string(ApiKeys::Name))
+                    .col(string(ApiKeys::KeyPrefix))
+                    .col(string(ApiKeys::KeyHash).unique_key())
+                    .col(string(ApiKeys::Scopes))
+                    .col(timestamp_with_time_zone_null(ApiKeys::ExpiresAt))
+                    .col(timestamp_with_time_zone_null(ApiKeys::RevokedAt))
+                    .col(timestamp_with_time_zone_null(ApiKeys::LastUsedAt))
+                    .col(
+                        timestamp_with_time_zone(ApiKeys::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(ApiKeys::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ApiKeys::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ApiKeys {
+    Table,
+    Id,
+    Name,
+    KeyPrefix,
+    KeyHash,
+    Scopes,
+    ExpiresAt,
+    RevokedAt,
+    LastUsedAt,
+    CreatedAt,
+    UpdatedAt,
+}
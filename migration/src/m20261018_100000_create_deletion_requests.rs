@@ -0,0 +1,63 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DeletionRequests::Table)
+                    .if_not_exists()
+                    .col(string(DeletionRequests::Id).primary_key())
+                    .col(string(DeletionRequests::UserId))
+                    .col(string(DeletionRequests::Status))
+                    .col(string(DeletionRequests::Step))
+                    .col(integer(DeletionRequests::Attempts).default(0))
+                    .col(text_null(DeletionRequests::LastError))
+                    .col(timestamp_with_time_zone_null(DeletionRequests::CompletedAt))
+                    .col(
+                        timestamp_with_time_zone(DeletionRequests::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(DeletionRequests::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_deletion_requests_user_id")
+                    .table(DeletionRequests::Table)
+                    .col(DeletionRequests::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DeletionRequests::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DeletionRequests {
+    Table,
+    Id,
+    UserId,
+    Status,
+    Step,
+    Attempts,
+    LastError,
+    CompletedAt,
+    CreatedAt,
+    UpdatedAt,
+}
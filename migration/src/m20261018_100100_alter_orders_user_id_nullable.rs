@@ -0,0 +1,31 @@
+use crate::m20250907_074341_create_orders::Orders;
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 退会時に注文を匿名化して残せるようにする
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Orders::Table)
+                    .modify_column(ColumnDef::new(Orders::UserId).string().null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Orders::Table)
+                    .modify_column(ColumnDef::new(Orders::UserId).string().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+}
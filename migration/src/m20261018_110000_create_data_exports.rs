@@ -0,0 +1,58 @@
+use crate::m20250907_074340_create_users::Users;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DataExports::Table)
+                    .if_not_exists()
+                    .col(string(DataExports::Id).primary_key())
+                    .col(string(DataExports::UserId))
+                    .col(string(DataExports::Status))
+                    .col(string_null(DataExports::ObjectKey))
+                    .col(text_null(DataExports::LastError))
+                    .col(timestamp_with_time_zone_null(DataExports::CompletedAt))
+                    .col(
+                        timestamp_with_time_zone(DataExports::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(DataExports::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_data_exports_user")
+                            .from(DataExports::Table, DataExports::UserId)
+                            .to(Users::Table, Users::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DataExports::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum DataExports {
+    Table,
+    Id,
+    UserId,
+    Status,
+    ObjectKey,
+    LastError,
+    CompletedAt,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,75 @@
+use crate::m20250907_074340_create_users::Users;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Assets::Table)
+                    .if_not_exists()
+                    .col(string(Assets::Id).primary_key())
+                    .col(string(Assets::UserId))
+                    .col(string(Assets::Key))
+                    .col(string(Assets::UploadKey))
+                    .col(string(Assets::Status))
+                    .col(string_null(Assets::ContentType))
+                    .col(big_integer_null(Assets::Size))
+                    .col(string_null(Assets::Checksum))
+                    .col(
+                        timestamp_with_time_zone(Assets::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(Assets::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_assets_user")
+                            .from(Assets::Table, Assets::UserId)
+                            .to(Users::Table, Users::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_assets_status_created_at")
+                    .table(Assets::Table)
+                    .col(Assets::Status)
+                    .col(Assets::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Assets::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Assets {
+    Table,
+    Id,
+    UserId,
+    Key,
+    UploadKey,
+    Status,
+    ContentType,
+    Size,
+    Checksum,
+    Width,
+    Height,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,33 @@
+use crate::m20261018_120000_create_assets::Assets;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 画像処理後に元画像のサイズを記録する
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Assets::Table)
+                    .add_column(integer_null(Assets::Width))
+                    .add_column(integer_null(Assets::Height))
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Assets::Table)
+                    .drop_column(Assets::Width)
+                    .drop_column(Assets::Height)
+                    .to_owned(),
+            )
+            .await
+    }
+}
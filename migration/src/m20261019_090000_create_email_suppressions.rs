@@ -0,0 +1,47 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(EmailSuppressions::Table)
+                    .if_not_exists()
+                    .col(string(EmailSuppressions::Id).primary_key())
+                    .col(string(EmailSuppressions::Email).unique_key())
+                    .col(string(EmailSuppressions::Reason))
+                    .col(string_null(EmailSuppressions::Detail))
+                    .col(
+                        timestamp_with_time_zone(EmailSuppressions::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(EmailSuppressions::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EmailSuppressions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum EmailSuppressions {
+    Table,
+    Id,
+    Email,
+    Reason,
+    Detail,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,64 @@
+use crate::m20250907_074340_create_users::Users;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(DeviceTokens::Table)
+                    .if_not_exists()
+                    .col(string(DeviceTokens::Id).primary_key())
+                    .col(string(DeviceTokens::UserId))
+                    .col(string(DeviceTokens::Token).unique_key())
+                    .col(string(DeviceTokens::Platform))
+                    .col(
+                        timestamp_with_time_zone(DeviceTokens::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(DeviceTokens::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_device_tokens_user")
+                            .from(DeviceTokens::Table, DeviceTokens::UserId)
+                            .to(Users::Table, Users::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_device_tokens_user_id")
+                    .table(DeviceTokens::Table)
+                    .col(DeviceTokens::UserId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(DeviceTokens::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum DeviceTokens {
+    Table,
+    Id,
+    UserId,
+    Token,
+    Platform,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,65 @@
+use crate::m20250907_074340_create_users::Users;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(NotificationPreferences::Table)
+                    .if_not_exists()
+                    .col(string(NotificationPreferences::Id).primary_key())
+                    .col(string(NotificationPreferences::UserId).unique_key())
+                    .col(string(NotificationPreferences::PushOptOuts).default(""))
+                    .col(string(NotificationPreferences::EmailOptOuts).default(""))
+                    .col(integer_null(NotificationPreferences::QuietHoursStart))
+                    .col(integer_null(NotificationPreferences::QuietHoursEnd))
+                    .col(
+                        timestamp_with_time_zone(NotificationPreferences::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(NotificationPreferences::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_notification_preferences_user")
+                            .from(
+                                NotificationPreferences::Table,
+                                NotificationPreferences::UserId,
+                            )
+                            .to(Users::Table, Users::Id),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(NotificationPreferences::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum NotificationPreferences {
+    Table,
+    Id,
+    UserId,
+    PushOptOuts,
+    EmailOptOuts,
+    QuietHoursStart,
+    QuietHoursEnd,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,59 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScheduledTasks::Table)
+                    .if_not_exists()
+                    .col(string(ScheduledTasks::Id).primary_key())
+                    .col(json_binary(ScheduledTasks::Payload))
+                    .col(timestamp_with_time_zone(ScheduledTasks::RunAt))
+                    .col(string(ScheduledTasks::Status))
+                    .col(
+                        timestamp_with_time_zone(ScheduledTasks::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .col(
+                        timestamp_with_time_zone(ScheduledTasks::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_scheduled_tasks_status_run_at")
+                    .table(ScheduledTasks::Table)
+                    .col(ScheduledTasks::Status)
+                    .col(ScheduledTasks::RunAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ScheduledTasks::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum ScheduledTasks {
+    Table,
+    Id,
+    Payload,
+    RunAt,
+    Status,
+    Attempts,
+    CreatedAt,
+    UpdatedAt,
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProcessedTasks::Table)
+                    .if_not_exists()
+                    .col(string(ProcessedTasks::Id).primary_key())
+                    .col(string(ProcessedTasks::TaskType))
+                    .col(
+                        timestamp_with_time_zone(ProcessedTasks::CreatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_processed_tasks_created_at")
+                    .table(ProcessedTasks::Table)
+                    .col(ProcessedTasks::CreatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProcessedTasks::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum ProcessedTasks {
+    Table,
+    Id,
+    TaskType,
+    Status,
+    CreatedAt,
+    UpdatedAt,
+}
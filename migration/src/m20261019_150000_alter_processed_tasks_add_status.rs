@@ -0,0 +1,36 @@
+use crate::m20261019_140000_create_processed_tasks::ProcessedTasks;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 処理前に記録を作って同じメッセージの同時処理を防ぐため、処理中かどうかを持たせる
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProcessedTasks::Table)
+                    .add_column(string(ProcessedTasks::Status).default("Completed"))
+                    .add_column(
+                        timestamp_with_time_zone(ProcessedTasks::UpdatedAt)
+                            .default(Expr::current_timestamp()),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ProcessedTasks::Table)
+                    .drop_column(ProcessedTasks::Status)
+                    .drop_column(ProcessedTasks::UpdatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,51 @@
+use crate::m20261019_130000_create_scheduled_tasks::ScheduledTasks;
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+// 投入し直す回数に上限を設けるため、投入した回数を記録する
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ScheduledTasks::Table)
+                    .add_column(integer(ScheduledTasks::Attempts).default(0))
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_scheduled_tasks_status_updated_at")
+                    .table(ScheduledTasks::Table)
+                    .col(ScheduledTasks::Status)
+                    .col(ScheduledTasks::UpdatedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_scheduled_tasks_status_updated_at")
+                    .table(ScheduledTasks::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(ScheduledTasks::Table)
+                    .drop_column(ScheduledTasks::Attempts)
+                    .to_owned(),
+            )
+            .await
+    }
+}